@@ -13,29 +13,45 @@
 //! 1 USDC / big = 1_000_000 lamports / smol
 //! 1 lamport / smol of USDC = 0.000_001 USDC / big
 
+use crate::error::ErrorCode::{self, MathFailure};
 use crate::math::{safe_div_i80f48, safe_mul_i80f48};
 use fixed::types::I80F48;
 
+fn ten_pow(decimals: u32) -> Result<I80F48, ErrorCode> {
+    10u64
+        .checked_pow(decimals)
+        .map(I80F48::from_num)
+        .ok_or(MathFailure)
+}
+
 /// Converts SOL -> lamports
-pub fn big_to_smol(unit: I80F48, decimals: u32) -> I80F48 {
-    safe_mul_i80f48(unit, I80F48::from_num(10u64.pow(decimals)))
+pub fn big_to_smol(unit: I80F48, decimals: u32) -> Result<I80F48, ErrorCode> {
+    safe_mul_i80f48(unit, ten_pow(decimals)?)
 }
 
 /// Converts lamports -> SOL
-pub fn smol_to_big(decimal: I80F48, decimals: u32) -> I80F48 {
-    safe_div_i80f48(decimal, I80F48::from_num(10u64.pow(decimals)))
+pub fn smol_to_big(decimal: I80F48, decimals: u32) -> Result<I80F48, ErrorCode> {
+    safe_div_i80f48(decimal, ten_pow(decimals)?)
 }
 
 /// Converts price denominated in main big/ big -> main smol/ smol (USDC / SOL -> smol USDC / lamports)
-pub fn big_price_to_smol_price(big: I80F48, main_decimal: u32, other_decimal: u32) -> I80F48 {
-    let step_one = safe_mul_i80f48(big, I80F48::from_num(10u64.pow(main_decimal)));
-    safe_div_i80f48(step_one, I80F48::from_num(10u64.pow(other_decimal)))
+pub fn big_price_to_smol_price(
+    big: I80F48,
+    main_decimal: u32,
+    other_decimal: u32,
+) -> Result<I80F48, ErrorCode> {
+    let step_one = safe_mul_i80f48(big, ten_pow(main_decimal)?)?;
+    safe_div_i80f48(step_one, ten_pow(other_decimal)?)
 }
 
 /// Converts price denominated in main smol/ smol -> main big/ big (smol USDC / lamports -> USDC / SOL)
-pub fn smol_price_to_big_price(smol: I80F48, main_decimal: u32, other_decimal: u32) -> I80F48 {
-    let step_one = safe_mul_i80f48(smol, I80F48::from_num(10u64.pow(other_decimal)));
-    safe_div_i80f48(step_one, I80F48::from_num(10u64.pow(main_decimal)))
+pub fn smol_price_to_big_price(
+    smol: I80F48,
+    main_decimal: u32,
+    other_decimal: u32,
+) -> Result<I80F48, ErrorCode> {
+    let step_one = safe_mul_i80f48(smol, ten_pow(other_decimal)?)?;
+    safe_div_i80f48(step_one, ten_pow(main_decimal)?)
 }
 
 #[cfg(test)]
@@ -48,7 +64,7 @@ mod tests {
             let big = I80F48::from_num(1); // 1 SOL
             let decimals = 9;
 
-            let smol = big_to_smol(big, decimals);
+            let smol = big_to_smol(big, decimals).unwrap();
 
             assert_eq!(smol, 1_000_000_000);
         }
@@ -56,7 +72,7 @@ mod tests {
             let big = I80F48::from_num(5); // 5 BTC
             let decimals = 8;
 
-            let smol = big_to_smol(big, decimals);
+            let smol = big_to_smol(big, decimals).unwrap();
 
             assert_eq!(smol, 5_0000_0000);
         }
@@ -64,7 +80,7 @@ mod tests {
             let big = I80F48::from_num(72); // 72 USDC
             let decimals = 6;
 
-            let smol = big_to_smol(big, decimals);
+            let smol = big_to_smol(big, decimals).unwrap();
 
             assert_eq!(smol, 72 * 1_000_000);
         }
@@ -76,7 +92,7 @@ mod tests {
             let smol = I80F48::from_num(1_000_000_000); // 1_000_000_000 lamports
             let decimals = 9;
 
-            let big = smol_to_big(smol, decimals);
+            let big = smol_to_big(smol, decimals).unwrap();
 
             assert_eq!(big, 1);
         }
@@ -84,7 +100,7 @@ mod tests {
             let smol = I80F48::from_num(5 * 1_0000_0000); // 5 * 1_0000_0000 satoshis
             let decimals = 8;
 
-            let big = smol_to_big(smol, decimals);
+            let big = smol_to_big(smol, decimals).unwrap();
 
             assert_eq!(big, 5);
         }
@@ -92,7 +108,7 @@ mod tests {
             let smol = I80F48::from_num(72 * 1_000_000); // 72 * 1_000_000 smol usdc
             let decimals = 6;
 
-            let big = smol_to_big(smol, decimals);
+            let big = smol_to_big(smol, decimals).unwrap();
 
             assert_eq!(big, 72);
         }
@@ -105,7 +121,7 @@ mod tests {
             let main_decimal = 6;
             let other_decimal = 9;
 
-            let smol_price = big_price_to_smol_price(big_price, main_decimal, other_decimal);
+            let smol_price = big_price_to_smol_price(big_price, main_decimal, other_decimal).unwrap();
 
             assert_eq!(smol_price, 50);
         }
@@ -114,10 +130,16 @@ mod tests {
             let main_decimal = 6;
             let other_decimal = 6;
 
-            let smol_price = big_price_to_smol_price(big_price, main_decimal, other_decimal);
+            let smol_price = big_price_to_smol_price(big_price, main_decimal, other_decimal).unwrap();
 
             assert_eq!(smol_price, 50_000);
         }
+        {
+            let big_price = I80F48::MAX;
+
+            assert!(big_price_to_smol_price(big_price, 6, 9).is_err());
+            assert!(big_price_to_smol_price(I80F48::ONE, 20, 6).is_err());
+        }
     }
 
     #[test]
@@ -127,7 +149,7 @@ mod tests {
             let main_decimal = 6;
             let other_decimal = 9;
 
-            let big_price = smol_price_to_big_price(smol_price, main_decimal, other_decimal);
+            let big_price = smol_price_to_big_price(smol_price, main_decimal, other_decimal).unwrap();
 
             assert_eq!(big_price, 50_000);
         }
@@ -136,7 +158,7 @@ mod tests {
             let main_decimal = 6;
             let other_decimal = 6;
 
-            let big_price = smol_price_to_big_price(smol_price, main_decimal, other_decimal);
+            let big_price = smol_price_to_big_price(smol_price, main_decimal, other_decimal).unwrap();
 
             assert_eq!(big_price, 50);
         }
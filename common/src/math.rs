@@ -35,22 +35,63 @@ where
 }
 
 // I80F48
-pub fn safe_add_i80f48(a: I80F48, b: I80F48) -> I80F48 {
-    let c = a.checked_add(b).ok_or(MathFailure);
-    c.unwrap()
+pub fn safe_add_i80f48(a: I80F48, b: I80F48) -> Result<I80F48, ErrorCode> {
+    a.checked_add(b).ok_or(MathFailure)
 }
 
-pub fn safe_sub_i80f48(a: I80F48, b: I80F48) -> I80F48 {
-    let c = a.checked_sub(b).ok_or(MathFailure);
-    c.unwrap()
+pub fn safe_sub_i80f48(a: I80F48, b: I80F48) -> Result<I80F48, ErrorCode> {
+    a.checked_sub(b).ok_or(MathFailure)
 }
 
-pub fn safe_mul_i80f48(a: I80F48, b: I80F48) -> I80F48 {
-    let c = a.checked_mul(b).ok_or(MathFailure);
-    c.unwrap()
+pub fn safe_mul_i80f48(a: I80F48, b: I80F48) -> Result<I80F48, ErrorCode> {
+    a.checked_mul(b).ok_or(MathFailure)
 }
 
-pub fn safe_div_i80f48(a: I80F48, b: I80F48) -> I80F48 {
-    let c = a.checked_div(b).ok_or(MathFailure);
-    c.unwrap()
+pub fn safe_div_i80f48(a: I80F48, b: I80F48) -> Result<I80F48, ErrorCode> {
+    a.checked_div(b).ok_or(MathFailure)
+}
+
+/// Raises `base` to an integer power by repeated squaring, failing on overflow.
+pub fn checked_pow_i80f48(base: I80F48, exp: u64) -> Result<I80F48, ErrorCode> {
+    let mut result = I80F48::ONE;
+    let mut base = base;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.checked_mul(base).ok_or(MathFailure)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.checked_mul(base).ok_or(MathFailure)?;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_pow_i80f48() {
+        assert_eq!(checked_pow_i80f48(I80F48::from_num(2), 0).unwrap(), 1);
+        assert_eq!(checked_pow_i80f48(I80F48::from_num(2), 10).unwrap(), 1024);
+        assert_eq!(checked_pow_i80f48(I80F48::from_num(3), 5).unwrap(), 243);
+        assert!(checked_pow_i80f48(I80F48::from_num(2), 200).is_err());
+    }
+
+    #[test]
+    fn test_safe_i80f48_ops() {
+        let a = I80F48::from_num(6);
+        let b = I80F48::from_num(3);
+        assert_eq!(safe_add_i80f48(a, b).unwrap(), 9);
+        assert_eq!(safe_sub_i80f48(a, b).unwrap(), 3);
+        assert_eq!(safe_mul_i80f48(a, b).unwrap(), 18);
+        assert_eq!(safe_div_i80f48(a, b).unwrap(), 2);
+
+        assert!(safe_add_i80f48(I80F48::MAX, b).is_err());
+        assert!(safe_sub_i80f48(I80F48::MIN, b).is_err());
+        assert!(safe_mul_i80f48(I80F48::MAX, b).is_err());
+        assert!(safe_div_i80f48(a, I80F48::ZERO).is_err());
+    }
 }
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+declare_id!("BKxNTS8DEdvePyTDW9dR4WfLu5wQQkmkB71yM3FLPVG6");
+
+// a separate program so zod's own IDL never carries a settable price. zod only reads these
+// accounts when built with its test-oracle feature, after set_oracle_source points a collateral at one
+#[program]
+pub mod zod_test_oracle {
+    use super::*;
+
+    pub fn set_price(cx: Context<SetPrice>, nonce: u8, price: i64, conf: u64, expo: i32) -> ProgramResult {
+        msg!("Instruction: SetPrice");
+
+        require!(price > 0, TestOracleError::InvalidPrice);
+
+        let oracle = &mut cx.accounts.oracle;
+        oracle.nonce = nonce;
+        oracle.authority = cx.accounts.authority.key();
+        oracle.mint = cx.accounts.mint.key();
+        oracle.price = price;
+        oracle.conf = conf;
+        oracle.expo = expo;
+        oracle.last_updated = Clock::get()?.unix_timestamp as u64;
+
+        emit!(TestOraclePriceSet {
+            oracle: oracle.key(),
+            mint: oracle.mint,
+            price,
+            conf,
+            expo,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u8)]
+pub struct SetPrice<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [authority.key.as_ref(), mint.key.as_ref(), b"zodtestoraclev1".as_ref()],
+        bump = nonce,
+        payer = authority,
+        space = 8 + size_of::<TestOracle>()
+    )]
+    pub oracle: Account<'info, TestOracle>,
+    pub system_program: Program<'info, System>,
+}
+
+// laid out like a pyth price so zod runs it through the same conversion
+#[account]
+#[derive(Default)]
+pub struct TestOracle {
+    pub nonce: u8,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub price: i64, // big usd per big collateral, scaled by 10^expo
+    pub conf: u64,
+    pub expo: i32,
+    pub last_updated: u64, // in seconds
+}
+
+#[event]
+pub struct TestOraclePriceSet {
+    pub oracle: Pubkey,
+    pub mint: Pubkey,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+}
+
+#[error]
+pub enum TestOracleError {
+    #[msg("Test oracle prices have to be positive.")]
+    InvalidPrice,
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+
+#[error]
+pub enum ZodError {
+    #[msg("Minting would push the PSM above its debt ceiling.")]
+    PsmDebtCeilingExceeded,
+    #[msg("The PSM does not hold enough reserve for this swap.")]
+    PsmInsufficientReserve,
+    #[msg("The PSM has already been initialized.")]
+    PsmAlreadyInitialized,
+    #[msg("The PSM has not been initialized.")]
+    PsmNotInitialized,
+    #[msg("Fee is out of range.")]
+    InvalidFee,
+    #[msg("A supplied margin has a lower collateral ratio than the redemption target.")]
+    RedemptionTargetNotRiskiest,
+    #[msg("Redemption amount exceeds the target margin's zod balance.")]
+    RedemptionExceedsDebt,
+    #[msg("The target margin does not hold enough of the requested collateral.")]
+    RedemptionCollateralInsufficient,
+    #[msg("Weight must leave a positive margin requirement above the liquidation fee.")]
+    InvalidWeight,
+    #[msg("Interest rate parameters are out of range.")]
+    InvalidRate,
+    #[msg("There is no pending admin to accept.")]
+    NoPendingAdmin,
+    #[msg("The admin transfer timelock has not elapsed yet.")]
+    AdminTimelockNotElapsed,
+    #[msg("This action is currently paused.")]
+    ActionPaused,
+    #[msg("Only the admin can unpause actions.")]
+    GuardianCannotUnpause,
+    #[msg("Deposit would exceed the cap for this collateral.")]
+    CollateralCapExceeded,
+    #[msg("Mint or psm swap would exceed the global zod debt ceiling.")]
+    DebtCeilingExceeded,
+    #[msg("Margin would fall below the initial margin requirement.")]
+    InsufficientMargin,
+    #[msg("Margin is above the maintenance margin requirement and cannot be liquidated.")]
+    NotLiquidatable,
+    #[msg("Margin has no zod balance to liquidate.")]
+    NothingToLiquidate,
+    #[msg("Margin still holds collateral above dust and is not bankrupt.")]
+    NotBankrupt,
+    #[msg("Oracle price is stale.")]
+    StaleOracle,
+    #[msg("Margin does not hold enough of this collateral.")]
+    InsufficientCollateral,
+    #[msg("Amount exceeds the margin's zod balance.")]
+    InsufficientZodBalance,
+    #[msg("Insurance fund does not hold enough to cover this.")]
+    InsufficientInsurance,
+    #[msg("Vault does not match the one registered for this collateral.")]
+    InvalidVault,
+    #[msg("A vault is already registered for this collateral.")]
+    VaultAlreadySet,
+    #[msg("Collateral does not exist.")]
+    CollateralDoesNotExist,
+    #[msg("Mint is not a registered collateral.")]
+    InvalidMint,
+    #[msg("Liquidation fee leaves no margin improvement per unit liquidated.")]
+    LiquidationFeeTooHigh,
+    #[msg("Loss to socialize exceeds the outstanding zod.")]
+    SocializedLossTooLarge,
+    #[msg("Math overflow or conversion failure.")]
+    MathFailure,
+    #[msg("Margin still holds zod debt, borrows or collateral above dust.")]
+    MarginNotEmpty,
+    #[msg("Token account is neither the margin's zod account nor the authority's associated token account.")]
+    InvalidTokenAccount,
+    #[msg("Liquidation needs at least one leg and at most one per collateral.")]
+    InvalidLiquidationLegs,
+    #[msg("Auctioned margin is still below its initial margin requirement.")]
+    AuctionStillActive,
+    #[msg("A flash mint is already outstanding.")]
+    FlashMintOutstanding,
+    #[msg("No flash mint is outstanding.")]
+    NoFlashMintOutstanding,
+    #[msg("Flash mint must be followed by a flash_repay in the same transaction.")]
+    MissingFlashRepay,
+    #[msg("Insurance shares are already initialized.")]
+    InsuranceSharesAlreadyInitialized,
+    #[msg("Insurance shares are not initialized.")]
+    InsuranceSharesNotInitialized,
+    #[msg("Insurance fund is depleted, outstanding shares are worthless.")]
+    InsuranceDepleted,
+    #[msg("Not enough insurance shares requested or held.")]
+    InsufficientInsuranceShares,
+    #[msg("Insurance withdrawal cooldown has not elapsed.")]
+    InsuranceCooldownNotElapsed,
+    #[msg("Insurance withdrawal window has expired, request again.")]
+    InsuranceWithdrawWindowExpired,
+    #[msg("Withdrawal would take insurance below its floor relative to outstanding zod.")]
+    InsuranceBelowFloor,
+    #[msg("Savings are already initialized.")]
+    SavingsAlreadyInitialized,
+    #[msg("Savings are not initialized.")]
+    SavingsNotInitialized,
+    #[msg("Unknown oracle source.")]
+    InvalidOracleSource,
+    #[msg("Oracle account could not be read as a price.")]
+    InvalidOracle,
+    #[msg("Pyth or switchboard account for a collateral was not passed in.")]
+    MissingOracleAccount,
+    #[msg("Oracle confidence interval is too wide relative to its price.")]
+    OracleConfidenceTooWide,
+    #[msg("Oracle price deviates too far from its secondary source.")]
+    OracleDeviationTooLarge,
+    #[msg("Test oracles are only available with the test-oracle feature.")]
+    TestOracleDisabled,
+    #[msg("Redemption target is above its initial margin requirement.")]
+    RedemptionTargetHealthy,
+    #[msg("Margin has an open liquidation auction, bid on it instead.")]
+    LiquidationAuctionOpen,
+    #[msg("Nothing is owed in this collateral, reduce insurance first.")]
+    NoInsuranceOwed,
+    #[msg("Savings deposit or withdrawal is too small to move any shares or zod.")]
+    SavingsAmountTooSmall,
+    #[msg("Oracle history hasn't been updated recently, crank update_oracle_history first.")]
+    OracleHistoryStale,
+    #[msg("The admin timelock can only be increased.")]
+    AdminTimelockDecrease,
+}
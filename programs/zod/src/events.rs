@@ -0,0 +1,261 @@
+use anchor_lang::prelude::*;
+
+use crate::zodTypes::WrappedI80F48;
+
+#[event]
+pub struct ZodParamsUpdated {
+    pub old_weight: u16,
+    pub new_weight: u16,
+    pub old_liq_fee: u16,
+    pub new_liq_fee: u16,
+    pub old_optimal_util: u16,
+    pub new_optimal_util: u16,
+    pub old_optimal_rate: u16,
+    pub new_optimal_rate: u16,
+    pub old_max_rate: u16,
+    pub new_max_rate: u16,
+    pub old_og_fee: u16,
+    pub new_og_fee: u16,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub eta: u64,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct PauseFlagsUpdated {
+    pub authority: Pubkey,
+    pub old_flags: u16,
+    pub new_flags: u16,
+}
+
+#[event]
+pub struct CollateralCapUpdated {
+    pub collateral_index: u8,
+    pub old_cap: u64,
+    pub new_cap: u64,
+}
+
+#[event]
+pub struct MinInsuranceRatioUpdated {
+    pub old_ratio: u16,
+    pub new_ratio: u16,
+}
+
+#[event]
+pub struct DebtCeilingUpdated {
+    pub old_ceiling: u64,
+    pub new_ceiling: u64,
+}
+
+#[event]
+pub struct ZodDeposited {
+    pub authority: Pubkey,
+    pub margin: Pubkey,
+    pub collateral_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ZodWithdrawn {
+    pub authority: Pubkey,
+    pub margin: Pubkey,
+    pub collateral_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ZodMinted {
+    pub authority: Pubkey,
+    pub margin: Pubkey,
+    pub amount: u64,
+    pub zod_balance: u64,
+}
+
+#[event]
+pub struct ZodBurned {
+    pub authority: Pubkey,
+    pub margin: Pubkey,
+    pub amount: u64,
+    pub zod_balance: u64,
+}
+
+#[event]
+pub struct ZodLiquidated {
+    pub liqor: Pubkey,
+    pub liqee: Pubkey,
+    pub collateral_index: u8,
+    pub zod_burned: u64,
+    pub collateral_seized: u64,
+    pub fee: u64, // part of collateral_seized above the zod burned, in smol collateral
+}
+
+#[event]
+pub struct LiquidationAuctionStarted {
+    pub keeper: Pubkey,
+    pub liqee: Pubkey,
+    pub auction: Pubkey,
+    pub start_time: u64,
+}
+
+#[event]
+pub struct LiquidationAuctionBid {
+    pub bidder: Pubkey,
+    pub liqee: Pubkey,
+    pub collateral_index: u8,
+    pub zod_burned: u64,
+    pub collateral_seized: u64,
+    pub discount_bps: u16,
+}
+
+#[event]
+pub struct LiquidationAuctionEnded {
+    pub liqee: Pubkey,
+    pub auction: Pubkey,
+    pub zod_repaid: u64,
+    pub duration: u64, // in seconds
+}
+
+#[event]
+pub struct BankruptcySettled {
+    pub liqor: Pubkey,
+    pub liqee: Pubkey,
+    pub zod_burned: u64,
+    pub insurance_used: u64,
+    pub socialized_amount: u64,
+    pub soc_loss_multiplier: WrappedI80F48,
+}
+
+#[event]
+pub struct InsuranceChanged {
+    pub authority: Pubkey,
+    pub collateral_index: u8,
+    pub amount: i64,
+    pub shares: i64,
+    pub insurance: u64, // held in collateral_index after the change
+}
+
+#[event]
+pub struct InsuranceSharesBurned {
+    pub authority: Pubkey,
+    pub shares: u64,
+}
+
+#[event]
+pub struct InsuranceClaimed {
+    pub authority: Pubkey,
+    pub collateral_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct InsuranceWithdrawalRequested {
+    pub authority: Pubkey,
+    pub shares: u64,
+    pub unlock_time: u64,
+}
+
+#[event]
+pub struct InsuranceWithdrawalCancelled {
+    pub authority: Pubkey,
+    pub shares: u64,
+    pub remaining_shares: u64,
+}
+
+#[event]
+pub struct PsmSwapped {
+    pub authority: Pubkey,
+    pub is_swap_in: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct PsmFeesCollected {
+    pub amount: u64, // in smol usdc
+}
+
+#[event]
+pub struct ZodRedeemed {
+    pub redeemer: Pubkey,
+    pub target: Pubkey,
+    pub collateral_index: u8,
+    pub zod_burned: u64,
+    pub collateral_out: u64,
+    pub fee_rate: WrappedI80F48,
+}
+
+#[event]
+pub struct ZodMarginClosed {
+    pub authority: Pubkey,
+    pub margin: Pubkey,
+    pub token_account_closed: bool,
+}
+
+#[event]
+pub struct FlashMintFeeUpdated {
+    pub old_fee: u16,
+    pub new_fee: u16,
+}
+
+#[event]
+pub struct ZodFlashMinted {
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct SavingsChanged {
+    pub authority: Pubkey,
+    pub amount: i64,
+    pub shares: i64,
+}
+
+#[event]
+pub struct SavingsDripped {
+    pub amount: u64,
+    pub savings_balance: u64,
+}
+
+#[event]
+pub struct ReserveRatioUpdated {
+    pub old_ratio: u16,
+    pub new_ratio: u16,
+}
+
+#[event]
+pub struct FeesCollected {
+    pub collateral_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct OracleSourceUpdated {
+    pub collateral_index: u8,
+    pub source: u8,
+    pub oracle: Pubkey,
+    pub deviation_source: u8,
+    pub deviation_oracle: Pubkey,
+}
+
+#[event]
+pub struct OracleGuardsUpdated {
+    pub max_confidence: u16,
+    pub max_deviation: u16,
+}
+
+#[event]
+pub struct OracleHistoryUpdated {
+    pub ema: [WrappedI80F48; 25],
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use common::time;
+
+use crate::errors::ZodError;
+use crate::events::AdminTransferred;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  // may be a multisig or governance pda signing through cpi
+  #[account(address = zod_state.load()?.pending_admin)]
+  pub pending_admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<AcceptAdmin>) -> ProgramResult {
+  msg!("Instruction: AcceptAdmin");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let current_time = time::get_current_time()?;
+
+  require!(
+    zod_state.pending_admin != Pubkey::default(),
+    ZodError::NoPendingAdmin
+  );
+  require!(
+    current_time >= zod_state.pending_admin_eta,
+    ZodError::AdminTimelockNotElapsed
+  );
+
+  let old_admin = zod_state.admin;
+  zod_state.admin = zod_state.pending_admin;
+  zod_state.pending_admin = Pubkey::default();
+  zod_state.pending_admin_eta = 0;
+
+  emit!(AdminTransferred {
+    old_admin,
+    new_admin: zod_state.admin,
+  });
+
+  Ok(())
+}
@@ -1,12 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::log::sol_log_compute_units;
-use anchor_spl::token::{Token, TokenAccount};
-use common::{system_program_utils, time};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::{system_program_utils, time, SafeOp};
 use fixed::types::I80F48;
 
+use crate::errors::ZodError;
+use crate::events::InsuranceChanged;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
 use crate::state::*;
 use crate::zodTypes::WrappedI80F48;
-use zo::errors::ErrorCode;
 
 use zo::{self, program::ZoAbi as Zo, cpi::accounts::*, *};
 
@@ -15,7 +18,7 @@ use zo::{self, program::ZoAbi as Zo, cpi::accounts::*, *};
 pub struct ZodAddInsurance<'info> {
   #[account(mut)]
   pub zod_state: AccountLoader<'info, ZodState>,
-  #[account(mut)]
+  #[account(mut, address = zod_state.load()?.zo_program_margin)]
   pub zo_program_margin: UncheckedAccount<'info>,
   pub zo_program: Program<'info, Zo>,
   #[account(address = zod_state.load()?.zo_program_state)]
@@ -42,11 +45,27 @@ pub struct ZodAddInsurance<'info> {
     constraint = zod_vault.mint == token_account.mint
   )]
   pub zod_vault: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    address = zod_state.load()?.insurance_share_mint,
+  )]
+  pub share_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = share_account.mint == share_mint.key(),
+  )]
+  pub share_account: Account<'info, TokenAccount>,
   pub token_program: Program<'info, Token>,
 }
 
 pub fn process(cx: Context<ZodAddInsurance>, amount: u64) -> ProgramResult {
   msg!("Instruction: ZodAddInsurance");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Insurance),
+    ZodError::ActionPaused
+  );
+
   let zod_state = &cx.accounts.zod_state;
   let zo_program_state = cx.accounts.zo_program_state.load()?;
   let token_acc = &cx.accounts.token_account;
@@ -55,13 +74,47 @@ pub fn process(cx: Context<ZodAddInsurance>, amount: u64) -> ProgramResult {
 
   let col_index = zo_program_state
     .get_collateral_index(&token_acc.mint)
-    .ok_or(ErrorCode::CollateralDoesNotExist)?;
-  assert!(col_index == 0);
-  assert!(zo_program_state.vaults[col_index] == zo_vault.key());
+    .ok_or(ZodError::CollateralDoesNotExist)?;
+  require!(
+    zo_program_state.vaults[col_index] == zo_vault.key(),
+    ZodError::InvalidVault
+  );
+  require!(
+    zod_state.load()?.vaults[col_index] == zod_vault.key(),
+    ZodError::InvalidVault
+  );
+
+  require!(
+    zod_state.load()?.is_insurance_shares_initialized(),
+    ZodError::InsuranceSharesNotInitialized
+  );
+
+  // priced against the fund before this deposit, with the same haircut the basket is valued at
+  let current_time = time::get_current_time()?;
+  let cache = cx.accounts.cache.load()?;
+  let insurance_value = zod_state
+    .load()?
+    .get_insurance_value(&zo_program_state, &cache, cx.remaining_accounts, current_time)?;
+  let weight = I80F48::from_num(zo_program_state.collaterals[col_index].weight).safe_div(1000i32)?;
+  let value = PriceReader::new(
+    &zo_program_state,
+    &cache,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    current_time,
+  )?
+  .get_price(col_index)?
+  .price
+    .safe_mul(weight)?
+    .safe_mul(I80F48::from_num(amount))?;
+  let shares =
+    ZodState::get_insurance_shares(value, insurance_value, cx.accounts.share_mint.supply)?;
+  debug_msg!("insurance deposit value: {}, shares: {}", value, shares);
+  drop(cache);
 
   let amount_i80: I80F48 = I80F48::from_num(amount);
   {
-    zod_state.load_mut()?.mutate_insurance(amount as i64)?;
+    zod_state.load_mut()?.mutate_insurance(col_index, amount as i64)?;
   }
   let amount_to_dep = amount_i80.ceil().to_num();
   system_program_utils::token_transfer(
@@ -85,7 +138,7 @@ pub fn process(cx: Context<ZodAddInsurance>, amount: u64) -> ProgramResult {
     token_program: cx.accounts.token_program.to_account_info(),
   };
 
-  let zod_state_seeds = &[b"zodv12".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
 
   let signers = &[&zod_state_seeds[..]];
 
@@ -93,5 +146,22 @@ pub fn process(cx: Context<ZodAddInsurance>, amount: u64) -> ProgramResult {
 
   zo::cpi::deposit(cpi_ctx, false, amount)?;
 
+  system_program_utils::mint_to(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.share_mint.to_account_info(),
+    cx.accounts.share_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    shares,
+    Some(signers),
+  )?;
+
+  emit!(InsuranceChanged {
+    authority: cx.accounts.authority.key(),
+    collateral_index: col_index as u8,
+    amount: amount as i64,
+    shares: shares as i64,
+    insurance: zod_state.load()?.insurance[col_index],
+  });
+
   Ok(())
 }
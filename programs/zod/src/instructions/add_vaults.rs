@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, TokenAccount};
 use std::convert::TryInto;
 
+use crate::errors::ZodError;
 use crate::state::*;
 use zo::{self, program::ZoAbi as Zo, cpi::accounts::*, *};
 
@@ -27,16 +28,15 @@ pub fn process(cx: Context<AddVaults>) -> ProgramResult {
   let zo_state = &mut cx.accounts.zo_state.load_mut()?;
   let mint = &cx.accounts.mint;
 
-  assert!(!zo_state.get_collateral_index(&mint.key()).is_none());
-  assert!(
-    zod_state.vaults[zo_state.get_collateral_index(&mint.key()).unwrap()] == Pubkey::default()
-  );
-
+  // vaults share zo's collateral indexing, so the slot is the mint's
+  // collateral index rather than the first empty one
   let index = zo_state
-    .collaterals
-    .iter()
-    .position(|col_info| *&col_info.is_empty())
-    .unwrap();
+    .get_collateral_index(&mint.key())
+    .ok_or(ZodError::CollateralDoesNotExist)?;
+  require!(
+    zod_state.vaults[index] == Pubkey::default(),
+    ZodError::VaultAlreadySet
+  );
 
   zod_state.vaults[index] = *cx.accounts.vault.to_account_info().key;
 
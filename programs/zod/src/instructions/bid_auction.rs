@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, *};
+use az::CheckedAs;
+use common::SafeOp;
+use common::time;
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::LiquidationAuctionBid;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+pub struct BidAuction<'info> {
+  #[account(mut)]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(
+    constraint = {zo_program_state.key() == zod_state.load()?.zo_program_state},
+  )]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  pub bidder: Signer<'info>,
+  #[account(
+    mut,
+    seeds = [bidder.key.as_ref(), zod_state.key().as_ref(), b"zodmarginv2".as_ref()],
+    bump = bidder_zod_margin.load()?.nonce
+  )]
+  pub bidder_zod_margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    mut,
+    seeds = [liqee_zod_margin.load()?.authority.as_ref(), zod_state.key().as_ref(), b"zodmarginv2".as_ref()],
+    bump = liqee_zod_margin.load()?.nonce
+  )]
+  pub liqee_zod_margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    mut,
+    seeds = [liqee_zod_margin.key().as_ref(), zod_state.key().as_ref(), b"zodauctionv1".as_ref()],
+    bump = auction.load()?.nonce
+  )]
+  pub auction: AccountLoader<'info, ZodLiquidationAuction>,
+  #[account(
+    mut,
+    constraint = {zod_mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub zod_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = {token_account.owner == *bidder.key},
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(
+  cx: Context<BidAuction>,
+  collateral_index: u8,
+  amount: u64,
+) -> ProgramResult {
+  msg!("Instruction: BidAuction");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Liquidate),
+    ZodError::ActionPaused
+  );
+
+  let zo_program_state = cx.accounts.zo_program_state.load()?;
+  let zod_state = &cx.accounts.zod_state;
+  let cache = &cx.accounts.cache;
+  let liqee_margin = &cx.accounts.liqee_zod_margin;
+  let bidder_margin = &cx.accounts.bidder_zod_margin;
+  let current_time = time::get_current_time()?;
+
+  let col_index = collateral_index as usize;
+  require!(
+    col_index < zo_program_state.total_collaterals as usize
+      && !zo_program_state.collaterals[col_index].is_empty(),
+    ZodError::CollateralDoesNotExist
+  );
+
+  zod_state.load_mut()?.accrue_stability_fee(current_time)?;
+  zod_state.load_mut()?.accrue_supply_yield(&cache.load()?)?;
+
+  let zod_balance: I80F48 = liqee_margin
+    .load()?
+    .get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+  require!(zod_balance > 0, ZodError::NothingToLiquidate);
+
+  let omf = liqee_margin.load()?.get_omf(
+    &zo_program_state,
+    &cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    None,
+    true,
+    current_time,
+  )?;
+  let imf = liqee_margin.load()?.get_imf(&zod_state.load()?)?;
+  let mmf = liqee_margin.load()?.get_mmf(&zod_state.load()?)?;
+
+  // the auction stays open until the margin is back above imf, after that only a new fall below mmf reopens it
+  require!(
+    cx.accounts.auction.load()?.is_biddable(omf, imf, mmf),
+    ZodError::NotLiquidatable
+  );
+
+  // the last bid restored the margin, so this is a new shortfall and the discount ramps up again
+  {
+    let mut auction = cx.accounts.auction.load_mut()?;
+    if auction.is_restored != 0 {
+      auction.start_time = current_time;
+      auction.is_restored = 0;
+    }
+  }
+
+  let col_info = &zo_program_state.collaterals[col_index];
+  let max_discount = ((1000 + zod_state.load()?.zod_token_info.liq_fee) as f64
+    / (1000 - col_info.liq_fee) as f64)
+    - 1.0;
+  let discount = cx
+    .accounts
+    .auction
+    .load()?
+    .get_discount(max_discount, current_time);
+  let num_lf = -1000.0 + col_info.weight as f64 * (1.0 + discount);
+
+  debug_msg!("max_discount: {}, discount: {}", max_discount, discount);
+
+  let max_assets_transfer =
+    liqee_margin.load()?.get_max_reducible(&zod_state.load()?, num_lf, imf, omf)?;
+
+  let mut assets_from_bidder = I80F48::from_num(max_assets_transfer)
+    .min(zod_balance)
+    .min(I80F48::from_num(amount));
+
+  // convert asset_price (smolUSD per asset) to asset_col_price (collateral per assets)
+  let col_price: I80F48 = PriceReader::new(
+    &zo_program_state,
+    &cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    current_time,
+  )?
+  .get_price(col_index)?
+  .price;
+
+  debug_msg!("col_price: {}", col_price);
+  let asset_col_price: I80F48 = I80F48::ONE.safe_div(col_price)?;
+
+  let pre_discount_col = assets_from_bidder
+    .safe_mul(asset_col_price)?
+    .floor()
+    .to_num::<i64>();
+  let mut col_to_bidder: i64 = (pre_discount_col as f64 * (1f64 + discount))
+    .checked_as()
+    .ok_or(ZodError::MathFailure)?;
+  debug_msg!("col_to_bidder {}", col_to_bidder);
+
+  let supply_multiplier: I80F48 = zod_state.load()?.get_supply_multiplier(col_index);
+  let borrow_multiplier: I80F48 = cache.load()?.borrow_cache[col_index]
+    .borrow_multiplier
+    .into();
+
+  // max amount of this collateral that liqee has
+  let max_col: i64 = liqee_margin
+    .load()?
+    .get_actual_collateral(col_index, supply_multiplier)?
+    .floor()
+    .to_num::<i64>()
+    .max(0);
+
+  if col_to_bidder > max_col {
+    col_to_bidder = max_col;
+    assets_from_bidder = I80F48::from_num(max_col)
+      .safe_div(asset_col_price.safe_mul(I80F48::from_num(1f64 + discount))?)?;
+    debug_msg!("assets_from_bidder_is_now_max_col: {}", assets_from_bidder);
+  }
+
+  require!(assets_from_bidder > 0, ZodError::NothingToLiquidate);
+
+  //mutate margins
+  liqee_margin.load_mut()?.zod_mutate(
+    -assets_from_bidder,
+    zod_state.load()?.get_borrow_multiplier()?,
+  )?;
+  zod_state
+    .load_mut()?
+    .mutate_zod_borrowed(-assets_from_bidder)?;
+  let liqee_stored = liqee_margin.load_mut()?.mutate(
+    col_index,
+    -I80F48::from_num(col_to_bidder),
+    supply_multiplier,
+    borrow_multiplier,
+  )?;
+  let bidder_stored = bidder_margin.load_mut()?.mutate(
+    col_index,
+    I80F48::from_num(col_to_bidder),
+    supply_multiplier,
+    borrow_multiplier,
+  )?;
+  zod_state
+    .load_mut()?
+    .mutate_total_collateral(col_index, liqee_stored.safe_add(bidder_stored)?)?;
+
+  let assets_from_bidder_u64 = assets_from_bidder.floor().to_num::<u64>();
+
+  let burn_cpi_program = cx.accounts.token_program.to_account_info();
+  let burn_cpi_accounts = Burn {
+    mint: cx.accounts.zod_mint.to_account_info(),
+    to: cx.accounts.token_account.to_account_info(),
+    authority: cx.accounts.bidder.to_account_info(),
+  };
+  let burn_cpi_ctx = CpiContext::new(burn_cpi_program, burn_cpi_accounts);
+  token::burn(burn_cpi_ctx, assets_from_bidder_u64)?;
+
+  let omf = liqee_margin.load()?.get_omf(
+    &zo_program_state,
+    &cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    None,
+    true,
+    current_time,
+  )?;
+  let imf = liqee_margin.load()?.get_imf(&zod_state.load()?)?;
+  debug_msg!("post bid omf: {}, imf: {}", omf, imf);
+
+  let mut auction = cx.accounts.auction.load_mut()?;
+  auction.zod_repaid = { auction.zod_repaid }.safe_add(assets_from_bidder_u64)?;
+  auction.is_restored = (omf >= imf) as u8;
+
+  emit!(LiquidationAuctionBid {
+    bidder: cx.accounts.bidder.key(),
+    liqee: liqee_margin.load()?.authority,
+    collateral_index,
+    zod_burned: assets_from_bidder_u64,
+    collateral_seized: col_to_bidder as u64,
+    discount_bps: (discount * 10_000f64) as u16,
+  });
+
+  Ok(())
+}
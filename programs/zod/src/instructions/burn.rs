@@ -6,16 +6,17 @@ use common::{system_program_utils, time};
 use fixed::types::I80F48;
 use zo::{self, cpi::accounts::*, program::ZoAbi as Zo, *};
 
+use crate::errors::ZodError;
+use crate::events::ZodBurned;
 use crate::state::*;
 use crate::zodTypes::WrappedI80F48;
-use zo::errors::ErrorCode;
 
 #[derive(Accounts)]
 #[instruction(amount: u64)]
 pub struct ZodBurn<'info> {
   #[account(
     mut,
-    seeds = [b"zodv12".as_ref()],
+    seeds = [b"zodv13".as_ref()],
     bump = zod_state.load()?.zod_state_nonce,
   )]
   pub zod_state: AccountLoader<'info, ZodState>,
@@ -42,14 +43,44 @@ pub struct ZodBurn<'info> {
 pub fn process(cx: Context<ZodBurn>, amount: u64) -> ProgramResult {
   msg!("Instruction ZodBurn");
 
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Burn),
+    ZodError::ActionPaused
+  );
+
   let zod_state = &cx.accounts.zod_state;
   let mut zod_margin = cx.accounts.zod_margin.load_mut()?;
-  let zod_balance: I80F48 = zod_margin.get_actual_zod_balance(zod_state.load()?.soc_loss_multiplier.into())?;
-  assert!(zod_balance > amount);
-  let amount_i80: I80F48 = I80F48::from_num(amount);
+  let current_time = time::get_current_time()?;
+
+  require!(
+    zod_margin.is_valid_zod_token_account(
+      &cx.accounts.token_account.key(),
+      &cx.accounts.mint.key()
+    ),
+    ZodError::InvalidTokenAccount
+  );
+
+  zod_state.load_mut()?.accrue_stability_fee(current_time)?;
+
+  let zod_balance: I80F48 = zod_margin.get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+  require!(zod_balance > I80F48::ZERO, ZodError::InsufficientZodBalance);
 
-  zod_margin.zod_mutate(-amount_i80, zod_state.load()?.soc_loss_multiplier.into())?;
-  zod_state.load_mut()?.mutate_zod_borrowed(-amount_i80)?;
+  // the debt keeps growing with the borrow multiplier, so no fixed amount lands it on zero.
+  // an amount at or above the debt burns its ceiling and clears the margin instead
+  let repay_all = I80F48::from_num(amount) >= zod_balance.ceil();
+  let amount: u64 = match repay_all {
+    true => zod_balance.ceil().to_num(),
+    false => amount,
+  };
+
+  if repay_all {
+    zod_margin.zod_balance = WrappedI80F48::zero();
+    zod_state.load_mut()?.mutate_zod_borrowed(-zod_balance)?;
+  } else {
+    let amount_i80: I80F48 = I80F48::from_num(amount);
+    zod_margin.zod_mutate(-amount_i80, zod_state.load()?.get_borrow_multiplier()?)?;
+    zod_state.load_mut()?.mutate_zod_borrowed(-amount_i80)?;
+  }
 
   let burn_cpi_program = cx.accounts.token_program.to_account_info();
   let burn_cpi_accounts = Burn {
@@ -62,5 +93,15 @@ pub fn process(cx: Context<ZodBurn>, amount: u64) -> ProgramResult {
 
   token::burn(burn_cpi_ctx, amount)?;
 
+  let zod_balance: I80F48 =
+    zod_margin.get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+
+  emit!(ZodBurned {
+    authority: cx.accounts.authority.key(),
+    margin: cx.accounts.zod_margin.key(),
+    amount,
+    zod_balance: zod_balance.floor().to_num::<u64>(),
+  });
+
   Ok(())
 }
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::ZodError;
+use crate::events::InsuranceWithdrawalCancelled;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(shares: u64)]
+pub struct CancelInsuranceWithdrawal<'info> {
+  #[account(
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    constraint = {share_account.owner == *authority.key},
+    constraint = share_account.mint == zod_state.load()?.insurance_share_mint,
+  )]
+  pub share_account: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [authority.key.as_ref(), zod_state.key().as_ref(), b"zodinsurancewithdrawv1".as_ref()],
+    bump,
+  )]
+  pub withdrawal: Account<'info, ZodInsuranceWithdrawal>,
+  #[account(
+    mut,
+    seeds = [withdrawal.key().as_ref(), b"zodinsuranceescrowv1".as_ref()],
+    bump,
+  )]
+  pub escrow: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<CancelInsuranceWithdrawal>, shares: u64) -> ProgramResult {
+  msg!("Instruction: CancelInsuranceWithdrawal");
+
+  // not gated by the insurance pause, handing back escrowed shares doesn't touch the fund
+  let withdrawal = &mut cx.accounts.withdrawal;
+  require!(
+    shares > 0 && shares <= withdrawal.shares,
+    ZodError::InsufficientInsuranceShares
+  );
+  withdrawal.shares -= shares;
+
+  let zod_state_seeds = &[
+    b"zodv13".as_ref(),
+    &[cx.accounts.zod_state.load()?.zod_state_nonce],
+  ];
+  let signer = &[&zod_state_seeds[..]];
+
+  let cpi_program = cx.accounts.token_program.to_account_info();
+  let cpi_accounts = Transfer {
+    from: cx.accounts.escrow.to_account_info(),
+    to: cx.accounts.share_account.to_account_info(),
+    authority: cx.accounts.zod_state.to_account_info(),
+  };
+  token::transfer(
+    CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+    shares,
+  )?;
+
+  // whatever is left in escrow keeps its cooldown, cancelling doesn't restart it
+  emit!(InsuranceWithdrawalCancelled {
+    authority: withdrawal.authority,
+    shares,
+    remaining_shares: withdrawal.shares,
+  });
+
+  Ok(())
+}
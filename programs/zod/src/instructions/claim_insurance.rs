@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use common::system_program_utils;
+
+use crate::errors::ZodError;
+use crate::events::InsuranceClaimed;
+use crate::state::*;
+
+use ::zo::cpi::accounts::Withdraw;
+use zo::{self, program::ZoAbi as Zo, *};
+
+#[derive(Accounts)]
+pub struct ClaimInsurance<'info> {
+  #[account(mut, address = zod_state.load()?.zo_program_margin)]
+  pub zo_program_margin: AccountLoader<'info, Margin>,
+  pub zo_program: Program<'info, Zo>,
+  #[account(
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(mut, address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(mut)]
+  pub state_signer: UncheckedAccount<'info>,
+  #[account(mut, address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  pub authority: Signer<'info>,
+  #[account(mut, address = zo_program_margin.load()?.control)]
+  pub control: AccountLoader<'info, Control>,
+  #[account(
+    mut,
+    constraint = {token_account.owner == *authority.key},
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = {zo_vault.owner == *state_signer.to_account_info().key},
+    constraint = zo_vault.mint == token_account.mint
+  )]
+  pub zo_vault: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = {zod_vault.owner == *zod_state.to_account_info().key},
+    constraint = zod_vault.mint == token_account.mint
+  )]
+  pub zod_vault: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [authority.key.as_ref(), zod_state.key().as_ref(), b"zodinsurancewithdrawv1".as_ref()],
+    bump,
+  )]
+  pub withdrawal: Account<'info, ZodInsuranceWithdrawal>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<ClaimInsurance>) -> ProgramResult {
+  msg!("Instruction: ClaimInsurance");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Insurance),
+    ZodError::ActionPaused
+  );
+
+  let zod_state = &cx.accounts.zod_state;
+  let col_index = cx
+    .accounts
+    .zo_program_state
+    .load()?
+    .get_collateral_index(&cx.accounts.token_account.mint)
+    .ok_or(ZodError::CollateralDoesNotExist)?;
+  require!(
+    cx.accounts.zo_program_state.load()?.vaults[col_index] == cx.accounts.zo_vault.key(),
+    ZodError::InvalidVault
+  );
+  require!(
+    zod_state.load()?.vaults[col_index] == cx.accounts.zod_vault.key(),
+    ZodError::InvalidVault
+  );
+
+  // already taken out of the fund by reduce_insurance
+  let withdrawal = &mut cx.accounts.withdrawal;
+  let amount = withdrawal.owed[col_index];
+  require!(amount > 0, ZodError::NoInsuranceOwed);
+  withdrawal.owed[col_index] = 0;
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  let signer = &[&zod_state_seeds[..]];
+
+  let cpi_program = cx.accounts.zo_program.to_account_info();
+  let cpi_accounts = Withdraw {
+    state: cx.accounts.zo_program_state.to_account_info(),
+    state_signer: cx.accounts.state_signer.to_account_info(),
+    cache: cx.accounts.cache.to_account_info(),
+    authority: cx.accounts.zod_state.to_account_info(),
+    margin: cx.accounts.zo_program_margin.to_account_info(),
+    control: cx.accounts.control.to_account_info(),
+    token_account: cx.accounts.zod_vault.to_account_info(),
+    vault: cx.accounts.zo_vault.to_account_info(),
+    token_program: cx.accounts.token_program.to_account_info(),
+  };
+  let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+  zo::cpi::withdraw(cpi_ctx, false, amount)?;
+
+  system_program_utils::token_transfer(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.zod_vault.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    amount,
+    Some(signer),
+  )?;
+
+  emit!(InsuranceClaimed {
+    authority: cx.accounts.authority.key(),
+    collateral_index: col_index as u8,
+    amount,
+  });
+
+  Ok(())
+}
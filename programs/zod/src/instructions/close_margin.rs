@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, *};
+use common::SafeOp;
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::ZodMarginClosed;
+use crate::log::debug_msg;
+use crate::state::*;
+use zo::{self, config::DEBUG_LOG, config::DUST_THRESHOLD, *};
+
+#[derive(Accounts)]
+pub struct CloseZodMargin<'info> {
+  #[account(mut)]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(
+    constraint = {zo_program_state.key() == zod_state.load()?.zo_program_state},
+  )]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    seeds = [authority.key.as_ref(), zod_state.key().as_ref(), b"zodmarginv2".as_ref()],
+    bump = margin.load()?.nonce,
+    close = authority
+  )]
+  pub margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    mut,
+    address = margin.load()?.zod_token_account,
+  )]
+  pub token_account: UncheckedAccount<'info>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<CloseZodMargin>, close_token_account: bool) -> ProgramResult {
+  msg!("Instruction: CloseZodMargin");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Withdraw),
+    ZodError::ActionPaused
+  );
+
+  let zo_program_state = cx.accounts.zo_program_state.load()?;
+  let margin = cx.accounts.margin.load()?;
+  let current_time = Clock::get()?.unix_timestamp as u64;
+
+  let mut zod_state = cx.accounts.zod_state.load_mut()?;
+  zod_state.accrue_stability_fee(current_time)?;
+  zod_state.accrue_supply_yield(&cx.accounts.cache.load()?)?;
+
+  // a full burn clears the debt, partial burns can leave a sliver that is written off like collateral dust
+  let zod_balance: I80F48 = margin.get_actual_zod_balance(zod_state.get_borrow_multiplier()?)?;
+  require!(
+    zod_balance >= I80F48::ZERO && zod_balance <= I80F48::from_num(DUST_THRESHOLD),
+    ZodError::MarginNotEmpty
+  );
+
+  // dust checks only look at positive value, so borrowed collateral has to be ruled out separately
+  let has_no_borrows = { margin.collateral }
+    .iter()
+    .all(|col| I80F48::from(*col) >= I80F48::ZERO);
+  require!(has_no_borrows, ZodError::MarginNotEmpty);
+
+  let below_dust = margin.has_no_col_above_dust(
+    &zo_program_state,
+    &cx.accounts.cache.load()?,
+    &zod_state,
+    cx.remaining_accounts,
+    current_time,
+  )?;
+  debug_msg!("below_dust: {:?}", below_dust);
+  require!(below_dust, ZodError::MarginNotEmpty);
+
+  // the dust stays with the program, so drop it from the global totals along with the margin
+  zod_state.mutate_zod_borrowed(-zod_balance)?;
+  for i in 0..zo_program_state.total_collaterals as usize {
+    let stored: I80F48 = { margin.collateral }[i].into();
+    if stored == I80F48::ZERO {
+      continue;
+    }
+    let actual = stored.safe_mul(zod_state.get_supply_multiplier(i))?;
+    zod_state.mutate_total_collateral(i, -stored)?;
+    zod_state.mutate_total_deposits(i, -actual.floor().to_num::<i64>())?;
+  }
+
+  if close_token_account {
+    // token program rejects this if the account still holds zod
+    let cpi_program = cx.accounts.token_program.to_account_info();
+    let cpi_accounts = CloseAccount {
+      account: cx.accounts.token_account.to_account_info(),
+      destination: cx.accounts.authority.to_account_info(),
+      authority: cx.accounts.authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::close_account(cpi_ctx)?;
+  }
+
+  msg!("{}/AUTH/{}", DEBUG_LOG, margin.authority);
+
+  emit!(ZodMarginClosed {
+    authority: margin.authority,
+    margin: cx.accounts.margin.key(),
+    token_account_closed: close_token_account,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use common::{system_program_utils, SafeOp};
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::FeesCollected;
+use crate::log::debug_msg;
+use crate::state::*;
+
+use ::zo::cpi::accounts::Withdraw;
+use zo::{self, program::ZoAbi as Zo, *};
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+  #[account(mut, address = zod_state.load()?.zo_program_margin)]
+  pub zo_program_margin: AccountLoader<'info, Margin>,
+  pub zo_program: Program<'info, Zo>,
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(mut, address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(mut)]
+  pub state_signer: UncheckedAccount<'info>,
+  #[account(mut, address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(mut, address = zo_program_margin.load()?.control)]
+  pub control: AccountLoader<'info, Control>,
+  #[account(mut)]
+  pub token_account: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = {zo_vault.owner == *state_signer.to_account_info().key},
+    constraint = zo_vault.mint == token_account.mint
+  )]
+  pub zo_vault: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = {zod_vault.owner == *zod_state.to_account_info().key},
+    constraint = zod_vault.mint == token_account.mint
+  )]
+  pub zod_vault: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<CollectFees>) -> ProgramResult {
+  msg!("Instruction: CollectFees");
+
+  let zo_program_state = &cx.accounts.zo_program_state;
+  let zod_state = &cx.accounts.zod_state;
+
+  let col_index = zo_program_state
+    .load()?
+    .get_collateral_index(&cx.accounts.token_account.mint)
+    .ok_or(ZodError::CollateralDoesNotExist)?;
+  require!(
+    zo_program_state.load()?.vaults[col_index] == cx.accounts.zo_vault.key(),
+    ZodError::InvalidVault
+  );
+  require!(
+    zod_state.load()?.vaults[col_index] == cx.accounts.zod_vault.key(),
+    ZodError::InvalidVault
+  );
+
+  let amount: u64;
+  {
+    let zod_state = &mut zod_state.load_mut()?;
+    zod_state.accrue_supply_yield(&cx.accounts.cache.load()?)?;
+
+    let accrued: I80F48 = zod_state.fees_accrued[col_index].into();
+    amount = accrued.floor().to_num::<u64>();
+    zod_state.fees_accrued[col_index] = accrued.safe_sub(I80F48::from_num(amount))?.into();
+  }
+  debug_msg!("fees collected of collateral {}: {}", col_index, amount);
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  let signer = &[&zod_state_seeds[..]];
+
+  let cpi_program = cx.accounts.zo_program.to_account_info();
+  let cpi_accounts = Withdraw {
+    state: cx.accounts.zo_program_state.to_account_info(),
+    state_signer: cx.accounts.state_signer.to_account_info(),
+    cache: cx.accounts.cache.to_account_info(),
+    authority: cx.accounts.zod_state.to_account_info(),
+    margin: cx.accounts.zo_program_margin.to_account_info(),
+    control: cx.accounts.control.to_account_info(),
+    token_account: cx.accounts.zod_vault.to_account_info(),
+    vault: cx.accounts.zo_vault.to_account_info(),
+    token_program: cx.accounts.token_program.to_account_info(),
+  };
+  let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+  zo::cpi::withdraw(cpi_ctx, false, amount)?;
+
+  system_program_utils::token_transfer(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.zod_vault.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    amount,
+    Some(signer),
+  )?;
+
+  emit!(FeesCollected {
+    collateral_index: col_index as u8,
+    amount,
+  });
+
+  Ok(())
+}
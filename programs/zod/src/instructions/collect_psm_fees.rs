@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use common::system_program_utils;
+
+use crate::errors::ZodError;
+use crate::events::PsmFeesCollected;
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CollectPsmFees<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(mut, address = zod_state.load()?.psm_vault)]
+  pub psm_vault: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = token_account.mint == zod_state.load()?.psm_mint,
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<CollectPsmFees>) -> ProgramResult {
+  msg!("Instruction: CollectPsmFees");
+
+  let amount: u64;
+  {
+    let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+    require!(zod_state.is_psm_initialized(), ZodError::PsmNotInitialized);
+
+    // fees are kept in the vault on top of the usdc backing psm_debt
+    amount = zod_state.psm_fees_accrued;
+    zod_state.psm_fees_accrued = 0;
+  }
+
+  debug_msg!("psm fees collected: {}", amount);
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signer = &[&zod_state_seeds[..]];
+
+  system_program_utils::token_transfer(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.psm_vault.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    amount,
+    Some(signer),
+  )?;
+
+  emit!(PsmFeesCollected { amount });
+
+  Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::{system_program_utils, time, SafeOp};
+use fixed::types::I80F48;
+
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CollectStabilityFees<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = token_account.mint == mint.key(),
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<CollectStabilityFees>) -> ProgramResult {
+  msg!("Instruction: CollectStabilityFees");
+
+  let current_time = time::get_current_time()?;
+  let amount: u64;
+  {
+    let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+    zod_state.accrue_stability_fee(current_time)?;
+
+    // fees are minted as zod, matching the debt they were charged on
+    let accrued: I80F48 = zod_state.stability_fees_accrued.into();
+    let stability_fees = accrued.floor().to_num::<u64>();
+    zod_state.stability_fees_accrued = accrued.safe_sub(I80F48::from_num(stability_fees))?.into();
+
+    // flash mint fees were burned on repay, so reissuing them adds no unbacked zod
+    let flash_mint_fees = zod_state.flash_mint_fees_accrued;
+    zod_state.flash_mint_fees_accrued = 0;
+
+    debug_msg!(
+      "stability fees collected: {}, flash mint fees collected: {}",
+      stability_fees,
+      flash_mint_fees
+    );
+    amount = stability_fees.safe_add(flash_mint_fees)?;
+  }
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signers = &[&zod_state_seeds[..]];
+
+  system_program_utils::mint_to(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.mint.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    amount,
+    Some(signers),
+  )?;
+
+  Ok(())
+}
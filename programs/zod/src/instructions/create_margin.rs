@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, *};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use fixed::types::I80F48;
 use std::mem::size_of;
 use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
@@ -21,11 +22,16 @@ pub struct CreateZodMargin<'info> {
       space = 8 + size_of::<ZodMargin>()
   )]
   pub margin: AccountLoader<'info, ZodMargin>,
-  #[account(mut)]
-  pub token_account: UncheckedAccount<'info>,
+  #[account(
+    init_if_needed,
+    payer = payer,
+    associated_token::mint = mint,
+    associated_token::authority = authority,
+  )]
+  pub token_account: Account<'info, TokenAccount>,
   pub token_program: Program<'info, Token>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
   #[account(
-    mut,
     constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
   )]
   pub mint: Account<'info, Mint>,
@@ -41,17 +47,6 @@ pub fn process(cx: Context<CreateZodMargin>, nonce: u8) -> ProgramResult {
   zod_margin.authority = *cx.accounts.authority.to_account_info().key;
   zod_margin.collateral = [WrappedI80F48::from(I80F48::ZERO); MAX_COLLATERALS as usize];
   zod_margin.zod_balance = WrappedI80F48::from(0);
-
-  let cpi_program = cx.accounts.token_program.to_account_info();
-  let cpi_accounts = InitializeAccount {
-    account: cx.accounts.token_account.to_account_info(),
-    mint: cx.accounts.mint.to_account_info(),
-    authority: cx.accounts.authority.to_account_info(),
-    rent: cx.accounts.rent.to_account_info(),
-  };
-  let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-  token::initialize_account(cpi_ctx)?;
-
   zod_margin.zod_token_account = cx.accounts.token_account.key();
 
   msg!("{}/AUTH/{}", DEBUG_LOG, zod_margin.authority,);
@@ -1,12 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::log::sol_log_compute_units;
 use anchor_spl::token::{Token, TokenAccount};
-use common::{system_program_utils, time};
+use common::{system_program_utils, time, SafeOp};
 use fixed::types::I80F48;
 
+use crate::errors::ZodError;
+use crate::events::ZodDeposited;
 use crate::state::*;
 use crate::zodTypes::WrappedI80F48;
-use zo::errors::ErrorCode;
 
 use ::zo::cpi::accounts::Deposit;
 use zo::{self, cpi::accounts::*, program::ZoAbi as Zo, *};
@@ -54,6 +55,12 @@ pub struct ZodDeposit<'info> {
 
 pub fn process(cx: Context<ZodDeposit>, amount: u64) -> ProgramResult {
   msg!("Instruction: ZodDeposit");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Deposit),
+    ZodError::ActionPaused
+  );
+
   let zod_state = &cx.accounts.zod_state;
   let zo_program_state = &cx.accounts.zo_program_state;
   let token_acc = &cx.accounts.token_account;
@@ -63,19 +70,34 @@ pub fn process(cx: Context<ZodDeposit>, amount: u64) -> ProgramResult {
   let col_index = zo_program_state
     .load()?
     .get_collateral_index(&token_acc.mint)
-    .ok_or(ErrorCode::CollateralDoesNotExist)?;
-  assert!(zo_program_state.load()?.vaults[col_index] == zo_vault.key());
+    .ok_or(ZodError::CollateralDoesNotExist)?;
+  require!(
+    zo_program_state.load()?.vaults[col_index] == zo_vault.key(),
+    ZodError::InvalidVault
+  );
+
+  {
+    let mut zod_state = zod_state.load_mut()?;
+    require!(
+      { zod_state.total_deposits[col_index] }.safe_add(amount)? <= zod_state.collateral_caps[col_index],
+      ZodError::CollateralCapExceeded
+    );
+    zod_state.mutate_total_deposits(col_index, amount as i64)?;
+  }
 
   let amount_i80: I80F48 = I80F48::from_num(amount);
   {
     let zod_margin = &mut cx.accounts.zod_margin.load_mut()?;
     let cache = &mut cx.accounts.cache.load_mut()?;
-    zod_margin.mutate(
+    let mut zod_state = zod_state.load_mut()?;
+    zod_state.accrue_supply_yield(cache)?;
+    let stored = zod_margin.mutate(
       col_index,
       amount_i80,
-      cache.borrow_cache[col_index].supply_multiplier.into(),
+      zod_state.get_supply_multiplier(col_index),
       cache.borrow_cache[col_index].borrow_multiplier.into(),
     )?;
+    zod_state.mutate_total_collateral(col_index, stored)?;
   }
   {
     let amount_to_dep = amount_i80.ceil().to_num();
@@ -101,7 +123,7 @@ pub fn process(cx: Context<ZodDeposit>, amount: u64) -> ProgramResult {
     token_program: cx.accounts.token_program.to_account_info(),
   };
 
-  let zod_state_seeds = &[b"zodv12".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
 
   let signers = &[&zod_state_seeds[..]];
 
@@ -109,5 +131,12 @@ pub fn process(cx: Context<ZodDeposit>, amount: u64) -> ProgramResult {
 
   zo::cpi::deposit(cpi_ctx, false, amount)?;
 
+  emit!(ZodDeposited {
+    authority: cx.accounts.authority.key(),
+    margin: cx.accounts.zod_margin.key(),
+    collateral_index: col_index as u8,
+    amount,
+  });
+
   Ok(())
 }
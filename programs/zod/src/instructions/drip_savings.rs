@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::{system_program_utils, time, SafeOp};
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::SavingsDripped;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+pub struct DripSavings<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  #[account(mut, address = zod_state.load()?.savings_vault)]
+  pub savings_vault: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<DripSavings>) -> ProgramResult {
+  msg!("Instruction: DripSavings");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Savings),
+    ZodError::ActionPaused
+  );
+
+  drip(
+    &cx.accounts.zod_state,
+    &cx.accounts.zo_program_state,
+    &cx.accounts.cache,
+    &cx.accounts.mint,
+    &cx.accounts.savings_vault,
+    &cx.accounts.token_program,
+    cx.remaining_accounts,
+    time::get_current_time()?,
+    false,
+  )?;
+
+  Ok(())
+}
+
+// mints the savings yield kept so far into the savings vault and returns the zod minted. the crank
+// leaves collaterals it can't price for the next drip, deposits and withdrawals pass strict so
+// no pending yield is left out of the share price
+#[allow(clippy::too_many_arguments)]
+pub fn drip<'info>(
+  zod_state: &AccountLoader<'info, ZodState>,
+  zo_program_state: &AccountLoader<'info, State>,
+  cache: &AccountLoader<'info, Cache>,
+  mint: &Account<'info, Mint>,
+  savings_vault: &Account<'info, TokenAccount>,
+  token_program: &Program<'info, Token>,
+  oracles: &[AccountInfo],
+  current_time: u64,
+  strict: bool,
+) -> Result<u64, ProgramError> {
+  let mut amount = I80F48::ZERO;
+  {
+    let zo_program_state = zo_program_state.load()?;
+    let cache = cache.load()?;
+    let zod_state = &mut zod_state.load_mut()?;
+    require!(zod_state.is_savings_initialized(), ZodError::SavingsNotInitialized);
+
+    zod_state.accrue_supply_yield(&cache)?;
+
+    // the kept collateral stays in the pooled 01 margin and backs the zod minted for it,
+    // valued with the same haircut as the insurance basket. collaterals whose price can't be read
+    // (stale, or its oracle account not passed in) are left for the next drip
+    let mut dripped = [false; 25];
+    for i in 0..zo_program_state.total_collaterals as usize {
+      let savings_yield: I80F48 = zod_state.savings_yield[i].into();
+      let info = &zo_program_state.collaterals[i];
+      if savings_yield == I80F48::ZERO || info.is_empty() {
+        continue;
+      }
+
+      let price = match PriceReader::new(
+        &zo_program_state,
+        &cache,
+        zod_state,
+        oracles,
+        current_time,
+      )?
+      .get_price(i)
+      {
+        Ok(price) => price.price,
+        Err(e) if strict => return Err(e),
+        Err(_) => continue,
+      };
+      let weight = I80F48::from_num(info.weight).safe_div(1000i32)?;
+
+      amount = amount.safe_add(savings_yield.safe_mul(price)?.safe_mul(weight)?)?;
+      dripped[i] = true;
+      debug_msg!("savings yield of collateral {}: {}", i, savings_yield);
+    }
+
+    // dripped zod has no margin debt behind it, so it counts against the debt ceiling like psm
+    // debt. past the ceiling the yield keeps waiting in savings_yield until the ceiling is raised
+    let minted = amount.floor().to_num::<u64>();
+    let total_debt = zod_state.get_total_zod_debt()?.safe_add(I80F48::from_num(minted))?;
+    if total_debt > zod_state.zod_debt_ceiling {
+      debug_msg!("savings drip of {} would exceed the debt ceiling", minted);
+      return Ok(0);
+    }
+    for (i, was_dripped) in dripped.iter().enumerate() {
+      if *was_dripped {
+        zod_state.savings_yield[i] = I80F48::ZERO.into();
+      }
+    }
+    zod_state.savings_minted = { zod_state.savings_minted }.safe_add(minted)?;
+  }
+
+  let amount = amount.floor().to_num::<u64>();
+  debug_msg!("savings dripped: {}", amount);
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  let signers = &[&zod_state_seeds[..]];
+
+  system_program_utils::mint_to(
+    token_program.to_account_info(),
+    mint.to_account_info(),
+    savings_vault.to_account_info(),
+    zod_state.to_account_info(),
+    amount,
+    Some(signers),
+  )?;
+
+  emit!(SavingsDripped {
+    amount,
+    savings_balance: savings_vault.amount.safe_add(amount)?,
+  });
+
+  Ok(amount)
+}
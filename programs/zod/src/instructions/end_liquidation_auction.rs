@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use common::time;
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::LiquidationAuctionEnded;
+use crate::log::debug_msg;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+pub struct EndLiquidationAuction<'info> {
+  #[account(mut)]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(
+    constraint = {zo_program_state.key() == zod_state.load()?.zo_program_state},
+  )]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(mut, address = auction.load()?.keeper)]
+  pub keeper: UncheckedAccount<'info>,
+  #[account(
+    seeds = [liqee_zod_margin.load()?.authority.as_ref(), zod_state.key().as_ref(), b"zodmarginv2".as_ref()],
+    bump = liqee_zod_margin.load()?.nonce
+  )]
+  pub liqee_zod_margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    mut,
+    seeds = [liqee_zod_margin.key().as_ref(), zod_state.key().as_ref(), b"zodauctionv1".as_ref()],
+    bump = auction.load()?.nonce,
+    close = keeper
+  )]
+  pub auction: AccountLoader<'info, ZodLiquidationAuction>,
+}
+
+pub fn process(cx: Context<EndLiquidationAuction>) -> ProgramResult {
+  msg!("Instruction: EndLiquidationAuction");
+
+  let liqee_margin = cx.accounts.liqee_zod_margin.load()?;
+  let current_time = time::get_current_time()?;
+
+  {
+    let mut zod_state = cx.accounts.zod_state.load_mut()?;
+    zod_state.accrue_stability_fee(current_time)?;
+    zod_state.accrue_supply_yield(&cx.accounts.cache.load()?)?;
+  }
+  let zod_state = cx.accounts.zod_state.load()?;
+
+  let zod_balance: I80F48 = liqee_margin.get_actual_zod_balance(zod_state.get_borrow_multiplier()?)?;
+  let is_settled = if zod_balance == I80F48::ZERO {
+    true
+  } else {
+    let omf = liqee_margin.get_omf(
+      &cx.accounts.zo_program_state.load()?,
+      &cx.accounts.cache.load()?,
+      &zod_state,
+      cx.remaining_accounts,
+      None,
+      true,
+      current_time,
+    )?;
+    let imf = liqee_margin.get_imf(&zod_state)?;
+    debug_msg!("omf: {}, imf: {}", omf, imf);
+    omf >= imf
+  };
+
+  require!(is_settled, ZodError::AuctionStillActive);
+
+  let auction = cx.accounts.auction.load()?;
+
+  emit!(LiquidationAuctionEnded {
+    liqee: liqee_margin.authority,
+    auction: cx.accounts.auction.key(),
+    zod_repaid: auction.zod_repaid,
+    duration: current_time.saturating_sub(auction.start_time),
+  });
+
+  Ok(())
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::sysvar::instructions::{
+  load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::system_program_utils;
+
+use crate::errors::ZodError;
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct FlashMint<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = token_account.mint == mint.key(),
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+  pub instructions: UncheckedAccount<'info>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<FlashMint>, amount: u64) -> ProgramResult {
+  msg!("Instruction: FlashMint");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::FlashMint),
+    ZodError::ActionPaused
+  );
+  require!(
+    cx.accounts.zod_state.load()?.flash_mint_outstanding == 0,
+    ZodError::FlashMintOutstanding
+  );
+
+  // solana only allows a program to reenter itself directly, so the zod is repaid by a later
+  // flash_repay instruction rather than a callback, which leaves keepers free to call zod in between
+  let ixs = cx.accounts.instructions.to_account_info();
+  let repay_sighash = &hash(b"global:flash_repay").to_bytes()[..8];
+  let current_index = load_current_index_checked(&ixs)? as usize;
+  let mut has_repay = false;
+  let mut index = current_index + 1;
+  while let Ok(ix) = load_instruction_at_checked(index, &ixs) {
+    if ix.program_id == crate::ID && ix.data.get(..8) == Some(repay_sighash) {
+      has_repay = true;
+      break;
+    }
+    index += 1;
+  }
+  require!(has_repay, ZodError::MissingFlashRepay);
+  debug_msg!("flash_repay found at instruction {}", index);
+
+  cx.accounts.zod_state.load_mut()?.flash_mint_outstanding = amount;
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signers = &[&zod_state_seeds[..]];
+
+  system_program_utils::mint_to(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.mint.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    amount,
+    Some(signers),
+  )?;
+
+  Ok(())
+}
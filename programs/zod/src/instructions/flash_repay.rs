@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, *};
+use common::bps::get_bps;
+use common::SafeOp;
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::ZodFlashMinted;
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = {token_account.owner == *authority.key},
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<FlashRepay>) -> ProgramResult {
+  msg!("Instruction: FlashRepay");
+
+  let amount: u64;
+  let fee: u64;
+  {
+    let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+    amount = zod_state.flash_mint_outstanding;
+    require!(amount > 0, ZodError::NoFlashMintOutstanding);
+
+    fee = I80F48::from_num(amount)
+      .safe_mul(get_bps(zod_state.flash_mint_fee))?
+      .ceil()
+      .to_num::<u64>();
+
+    // the fee is burned with the principal, so it is surplus backing that can be minted later
+    zod_state.flash_mint_fees_accrued = { zod_state.flash_mint_fees_accrued }.safe_add(fee)?;
+    zod_state.flash_mint_outstanding = 0;
+  }
+
+  debug_msg!("flash repay amount: {}, fee: {}", amount, fee);
+
+  let burn_cpi_program = cx.accounts.token_program.to_account_info();
+  let burn_cpi_accounts = Burn {
+    mint: cx.accounts.mint.to_account_info(),
+    to: cx.accounts.token_account.to_account_info(),
+    authority: cx.accounts.authority.to_account_info(),
+  };
+  let burn_cpi_ctx = CpiContext::new(burn_cpi_program, burn_cpi_accounts);
+  token::burn(burn_cpi_ctx, amount.safe_add(fee)?)?;
+
+  emit!(ZodFlashMinted {
+    authority: cx.accounts.authority.key(),
+    amount,
+    fee,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::{system_program_utils, time};
+
+use crate::errors::ZodError;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(cooldown: u64, withdraw_window: u64)]
+pub struct InitInsuranceShares<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(
+    mut,
+    constraint = share_mint.mint_authority == COption::Some(zod_state.key()),
+    constraint = share_mint.supply == 0,
+  )]
+  pub share_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = share_account.mint == share_mint.key(),
+  )]
+  pub share_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<InitInsuranceShares>, cooldown: u64, withdraw_window: u64) -> ProgramResult {
+  msg!("Instruction: InitInsuranceShares");
+
+  let insurance: u64;
+  let current_time = time::get_current_time()?;
+  {
+    let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+    require!(
+      !zod_state.is_insurance_shares_initialized(),
+      ZodError::InsuranceSharesAlreadyInitialized
+    );
+
+    zod_state.insurance_share_mint = cx.accounts.share_mint.key();
+    zod_state.insurance_cooldown = cooldown;
+    zod_state.insurance_withdraw_window = withdraw_window;
+    insurance = zod_state
+      .get_insurance_value(
+        &cx.accounts.zo_program_state.load()?,
+        &cx.accounts.cache.load()?,
+        cx.remaining_accounts,
+        current_time,
+      )?
+      .floor()
+      .to_num::<u64>();
+  }
+
+  // insurance added before shares existed belongs to whoever the admin hands these to
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signers = &[&zod_state_seeds[..]];
+
+  system_program_utils::mint_to(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.share_mint.to_account_info(),
+    cx.accounts.share_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    insurance,
+    Some(signers),
+  )?;
+
+  Ok(())
+}
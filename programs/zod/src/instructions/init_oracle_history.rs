@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(nonce: u8, period: u64)]
+pub struct InitOracleHistory<'info> {
+  #[account(
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(mut, address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(
+    init,
+    seeds = [zod_state.key().as_ref(), b"zodoraclehistoryv1".as_ref()],
+    bump = nonce,
+    payer = admin,
+    space = 8 + size_of::<ZodOracleHistory>()
+  )]
+  pub oracle_history: AccountLoader<'info, ZodOracleHistory>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn process(cx: Context<InitOracleHistory>, nonce: u8, period: u64) -> ProgramResult {
+  msg!("Instruction: InitOracleHistory");
+
+  // a zero period makes the average follow spot
+  let history = &mut cx.accounts.oracle_history.load_init()?;
+  history.nonce = nonce;
+  history.period = period;
+
+  Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::ZodError;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(tin: u16, tout: u16, debt_ceiling: u64)]
+pub struct InitPsm<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(constraint = psm_mint.decimals == zod_state.load()?.zod_token_info.decimals)]
+  pub psm_mint: Account<'info, Mint>,
+  #[account(
+    constraint = {psm_vault.owner == *zod_state.to_account_info().key},
+    constraint = psm_vault.mint == psm_mint.key(),
+  )]
+  pub psm_vault: Account<'info, TokenAccount>,
+}
+
+pub fn process(cx: Context<InitPsm>, tin: u16, tout: u16, debt_ceiling: u64) -> ProgramResult {
+  msg!("Instruction: InitPsm");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+
+  require!(!zod_state.is_psm_initialized(), ZodError::PsmAlreadyInitialized);
+  require!(tin <= 10_000 && tout <= 10_000, ZodError::InvalidFee);
+
+  zod_state.psm_mint = cx.accounts.psm_mint.key();
+  zod_state.psm_vault = cx.accounts.psm_vault.key();
+  zod_state.psm_tin = tin;
+  zod_state.psm_tout = tout;
+  zod_state.psm_debt_ceiling = debt_ceiling;
+  zod_state.psm_debt = 0;
+  zod_state.psm_fees_accrued = 0;
+
+  Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{Mint, TokenAccount};
+use common::SafeOp;
+
+use crate::errors::ZodError;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(yield_share: u16)]
+pub struct InitSavings<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(
+    constraint = savings_mint.mint_authority == COption::Some(zod_state.key()),
+    constraint = savings_mint.supply == 0,
+    constraint = savings_mint.decimals == zod_state.load()?.zod_token_info.decimals,
+  )]
+  pub savings_mint: Account<'info, Mint>,
+  #[account(
+    constraint = {savings_vault.owner == *zod_state.to_account_info().key},
+    constraint = savings_vault.mint == zod_state.load()?.zod_token_info.mint,
+  )]
+  pub savings_vault: Account<'info, TokenAccount>,
+}
+
+pub fn process(cx: Context<InitSavings>, yield_share: u16) -> ProgramResult {
+  msg!("Instruction: InitSavings");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+
+  require!(!zod_state.is_savings_initialized(), ZodError::SavingsAlreadyInitialized);
+  require!(
+    { zod_state.reserve_ratio }.safe_add(yield_share)? <= 10_000,
+    ZodError::InvalidFee
+  );
+
+  // yield up to now belongs to depositors, only growth from here on is split
+  zod_state.accrue_supply_yield(&cx.accounts.cache.load()?)?;
+
+  zod_state.savings_mint = cx.accounts.savings_mint.key();
+  zod_state.savings_vault = cx.accounts.savings_vault.key();
+  zod_state.savings_yield_share = yield_share;
+
+  Ok(())
+}
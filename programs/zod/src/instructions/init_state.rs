@@ -16,7 +16,7 @@ pub struct InitZodState<'info> {
   pub admin: Signer<'info>,
   #[account(
     init_if_needed,
-    seeds = [b"zodv12".as_ref()],
+    seeds = [b"zodv13".as_ref()],
     bump = zod_state_nonce,
     payer = admin,
     space = 8 + size_of::<ZodState>()
@@ -49,14 +49,23 @@ pub fn process(
     st.zo_margin_nonce = zo_margin_nonce;
     st.admin = *cx.accounts.admin.to_account_info().key;
     st.zo_program_state = cx.accounts.zo_program_state.key();
-    st.insurance = 0;
+    st.insurance = [0; 25];
     st.total_collaterals = 25;
     st.zo_program_margin = cx.accounts.zo_program_margin.key();
     st.total_zod_borrowed = WrappedI80F48::from(0i8);
     st.soc_loss_multiplier = WrappedI80F48::from(1i8);
+    st.debt_multiplier = WrappedI80F48::from(1i8);
+    st.stability_fee_rate = WrappedI80F48::from(0i8);
+    st.stability_fees_accrued = WrappedI80F48::from(0i8);
+    st.last_fee_accrual = Clock::get()?.unix_timestamp as u64;
+    st.redemption_base_rate = WrappedI80F48::from(0i8);
+    st.last_redemption_time = st.last_fee_accrual;
+    st.redemption_fee_floor = 50;
+    st.collateral_caps = [u64::MAX; 25];
+    st.zod_debt_ceiling = u64::MAX;
   }
 
-  let zod_state_seeds = &[b"zodv12".as_ref(), &[zod_state_nonce]];
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state_nonce]];
 
   //creating associated zo program margin
   let margin_cpi_program = cx.accounts.zo_program.to_account_info();
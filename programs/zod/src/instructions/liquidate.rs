@@ -7,8 +7,11 @@ use common::{system_program_utils, time};
 use fixed::types::I80F48;
 use zo::config::{SPOT_INITIAL_MARGIN_REQ, SPOT_MAINT_MARGIN_REQ};
 
+use crate::errors::ZodError;
+use crate::events::ZodLiquidated;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
 use crate::state::*;
-use zo::errors::ErrorCode;
 use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
 
 #[derive(Accounts)]
@@ -35,12 +38,17 @@ pub struct LiquidateZodPosition<'info> {
     bump =liqee_zod_margin.load()?.nonce
   )]
   pub liqee_zod_margin: AccountLoader<'info, ZodMargin>,
+  // must not hold an open auction, see start_liquidation_auction
+  #[account(
+    seeds = [liqee_zod_margin.key().as_ref(), zod_state.key().as_ref(), b"zodauctionv1".as_ref()],
+    bump
+  )]
+  pub auction: UncheckedAccount<'info>,
   #[account(
     mut,
     constraint = {zod_mint.key() == zod_state.load()?.zod_token_info.mint},
   )]
   pub zod_mint: Account<'info, Mint>,
-  pub quote_mint: Account<'info, Mint>,
   #[account(
     mut,
     constraint = {token_account.owner == *liqor.key},
@@ -49,143 +57,217 @@ pub struct LiquidateZodPosition<'info> {
   pub token_program: Program<'info, Token>,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct LiquidationLeg {
+  pub collateral_index: u8,
+  pub amount: u64, // max zod to repay against this collateral
+}
+
 pub fn process(
   cx: Context<LiquidateZodPosition>,
-  asset_transfer_amount: u64,
-  _mock_col_price: Option<u64>,
+  legs: Vec<LiquidationLeg>,
 ) -> ProgramResult {
 
   msg!("Instruction: LiquidateZodPosition");
 
-  #[cfg(feature = "devnet")]
-  msg!("mock collateral price: {:?}", _mock_col_price);
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Liquidate),
+    ZodError::ActionPaused
+  );
 
   let zo_program_state = cx.accounts.zo_program_state.load()?;
   let zod_state = &cx.accounts.zod_state;
   let cache = &cx.accounts.cache;
 
-  let quote_col_index = zo_program_state
-    .get_collateral_index(cx.accounts.quote_mint.to_account_info().key)
-    .ok_or(ErrorCode::InvalidMint)?;
+  require!(
+    !legs.is_empty() && legs.len() <= MAX_COLLATERALS as usize,
+    ZodError::InvalidLiquidationLegs
+  );
+
+  // once an auction is open the margin can only be liquidated through bids
+  require!(
+    cx.accounts.auction.data_is_empty(),
+    ZodError::LiquidationAuctionOpen
+  );
 
   let current_time = time::get_current_time()?;
   let liqee_margin = &cx.accounts.liqee_zod_margin;
   let liqor_margin = &cx.accounts.liqor_zod_margin;
+  let liqee = liqee_margin.load()?.authority;
+
+  zod_state.load_mut()?.accrue_stability_fee(current_time)?;
+  zod_state.load_mut()?.accrue_supply_yield(&cache.load()?)?;
 
   let zod_balance: I80F48 = liqee_margin
     .load()?
-    .get_actual_zod_balance(zod_state.load()?.soc_loss_multiplier.into())?;
-  assert!(zod_balance > 0);
+    .get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+  require!(zod_balance > 0, ZodError::NothingToLiquidate);
 
   let omf = liqee_margin.load()?.get_omf(
     &zo_program_state,
     &cache.load()?,
     &zod_state.load()?,
+    cx.remaining_accounts,
+    None,
     true,
     current_time,
-    _mock_col_price,
   )?;
 
-  let imf = liqee_margin.load()?.get_imf(&zod_state.load()?)?;
-
   let mmf = liqee_margin.load()?.get_mmf(&zod_state.load()?)?;
 
   //making sure that collateral can be liquidated
-  assert!(omf < mmf);
-
-  let liq_fee = ((1000 + zod_state.load()?.zod_token_info.liq_fee) as f64
-    / (1000 - zo_program_state.collaterals[quote_col_index].liq_fee) as f64)
-    - 1.0;
-  let num_lf =
-    -1000.0 + zo_program_state.collaterals[quote_col_index].weight as f64 * (1.0 + liq_fee);
-
-  msg!("liq_fee: {}", liq_fee);
-
-  let max_assets_transfer = liqee_margin.load()?.get_max_reducible(&zod_state.load()?, num_lf, imf, omf)?;
-
-  msg!("asset_transfer_amount: {:?}", asset_transfer_amount);
-
-  let mut assets_from_liqor = I80F48::from_num(max_assets_transfer)
-    .min(zod_balance)
-    .min(I80F48::from_num(asset_transfer_amount));
-
-  // get quote to transfer
-  // convert asset_price (smolUSD per asset) to asset_quote_price (quote per assets)
-  let quote_oracle = cache
-    .load()?
-    .get_oracle(&zo_program_state.collaterals[quote_col_index].oracle_symbol)?
-    .clone();
-  let mut quote_price: I80F48 = quote_oracle.price.into();
-  require!(!quote_oracle.is_stale(current_time), OracleCacheStale);
-
-  #[cfg(feature = "devnet")]
-  if let Some(_mock_col_price) = _mock_col_price {
-    quote_price = I80F48::from_num(_mock_col_price as f64 / 1000.0);
-  }
-  msg!("quote_price: {}", quote_price);
-  let asset_quote_price: I80F48 = I80F48::ONE.safe_div(quote_price)?;
-
-  let pre_fee_quote = assets_from_liqor
-    .safe_mul(asset_quote_price)?
-    .floor()
-    .to_num::<i64>();
-  msg!("pre_fee_quote {}", pre_fee_quote);
-  msg!("asset_quote_price {}", asset_quote_price);
-  let mut quote_to_liqor: i64 = (pre_fee_quote as f64 * (1f64 + liq_fee))
-    .checked_as()
-    .unwrap();
-  msg!("quote fee multiplier: {}", 1f64 + liq_fee as f64);
-  //quote_to_liqor = -quote_to_liqor;
-  msg!("quote_to_liqor {}", quote_to_liqor);
-
-  // max amount of quote col that liqee has
-  let max_quote_col: i64 = liqee_margin
-    .load()?
-    .get_actual_collateral(
-      quote_col_index,
-      cache.load()?.borrow_cache[quote_col_index]
-        .supply_multiplier
-        .into(),
+  require!(omf < mmf, ZodError::NotLiquidatable);
+
+  let mut total_zod_burned: u64 = 0;
+  let mut seen_legs: u32 = 0;
+
+  for leg in legs.iter() {
+    let col_index = leg.collateral_index as usize;
+    require!(
+      col_index < zo_program_state.total_collaterals as usize
+        && !zo_program_state.collaterals[col_index].is_empty(),
+      ZodError::CollateralDoesNotExist
+    );
+    require!(
+      seen_legs & (1 << col_index) == 0,
+      ZodError::InvalidLiquidationLegs
+    );
+    seen_legs |= 1 << col_index;
+    debug_msg!("liquidation leg: {:?}", leg);
+
+    // each leg moves the margin closer to imf, so the reducible amount is recomputed every time
+    let zod_balance: I80F48 = liqee_margin
+      .load()?
+      .get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+    let omf = liqee_margin.load()?.get_omf(
+      &zo_program_state,
+      &cache.load()?,
+      &zod_state.load()?,
+      cx.remaining_accounts,
+      None,
+      true,
+      current_time,
+    )?;
+    let imf = liqee_margin.load()?.get_imf(&zod_state.load()?)?;
+
+    let col_info = &zo_program_state.collaterals[col_index];
+    let liq_fee = ((1000 + zod_state.load()?.zod_token_info.liq_fee) as f64
+      / (1000 - col_info.liq_fee) as f64)
+      - 1.0;
+    let num_lf = -1000.0 + col_info.weight as f64 * (1.0 + liq_fee);
+
+    debug_msg!("liq_fee: {}", liq_fee);
+
+    let max_assets_transfer =
+      liqee_margin.load()?.get_max_reducible(&zod_state.load()?, num_lf, imf, omf)?;
+
+    let mut assets_from_liqor = I80F48::from_num(max_assets_transfer)
+      .min(zod_balance)
+      .min(I80F48::from_num(leg.amount));
+
+    if assets_from_liqor <= 0 {
+      debug_msg!("margin is back above imf, skipping remaining legs");
+      break;
+    }
+
+    // get collateral to transfer
+    // convert asset_price (smolUSD per asset) to asset_col_price (collateral per assets)
+    let col_price: I80F48 = PriceReader::new(
+      &zo_program_state,
+      &cache.load()?,
+      &zod_state.load()?,
+      cx.remaining_accounts,
+      current_time,
     )?
-    .floor()
-    .to_num::<i64>();
-  msg!(
-    "max_quote_col and alice quote col before: {}",
-    max_quote_col
-  );
-
-  if quote_to_liqor > max_quote_col {
-    quote_to_liqor = max_quote_col;
-    // todo: check if the fee math here is right
-    msg!("changing assets_from_liqor");
-    assets_from_liqor = I80F48::from_num(max_quote_col)
-      .safe_div(asset_quote_price.safe_mul(I80F48::from_num(1f64 + liq_fee))?)?;
-    msg!("assets_from_liqor_is_now_max_col: {}", assets_from_liqor);
-  }
-
-  msg!("assets_from_liqor {:?}", assets_from_liqor);
-
-  //mutate margins
-  liqee_margin.load_mut()?.zod_mutate(
-    -assets_from_liqor,
-    zod_state.load()?.soc_loss_multiplier.into(),
-  )?;
-  zod_state
-    .load_mut()?
-    .mutate_zod_borrowed(-assets_from_liqor)?;
-  liqee_margin.load_mut()?.mutate(
-    quote_col_index,
-    -I80F48::from_num(quote_to_liqor),
-    cache.load()?.borrow_cache[quote_col_index]
-      .supply_multiplier
-      .into(),
-    cache.load()?.borrow_cache[quote_col_index]
+    .get_price(col_index)?
+    .price;
+
+    debug_msg!("col_price: {}", col_price);
+    let asset_col_price: I80F48 = I80F48::ONE.safe_div(col_price)?;
+
+    let pre_fee_col = assets_from_liqor
+      .safe_mul(asset_col_price)?
+      .floor()
+      .to_num::<i64>();
+    debug_msg!("pre_fee_col {}", pre_fee_col);
+    debug_msg!("asset_col_price {}", asset_col_price);
+    let mut col_to_liqor: i64 = (pre_fee_col as f64 * (1f64 + liq_fee))
+      .checked_as()
+      .ok_or(ZodError::MathFailure)?;
+    debug_msg!("col fee multiplier: {}", 1f64 + liq_fee as f64);
+    debug_msg!("col_to_liqor {}", col_to_liqor);
+
+    let supply_multiplier: I80F48 = zod_state.load()?.get_supply_multiplier(col_index);
+    let borrow_multiplier: I80F48 = cache.load()?.borrow_cache[col_index]
       .borrow_multiplier
-      .into(),
-  )?;
+      .into();
+
+    // max amount of this collateral that liqee has
+    let max_col: i64 = liqee_margin
+      .load()?
+      .get_actual_collateral(col_index, supply_multiplier)?
+      .floor()
+      .to_num::<i64>()
+      .max(0);
+    debug_msg!("max_col and liqee col before: {}", max_col);
+
+    if col_to_liqor > max_col {
+      col_to_liqor = max_col;
+      // todo: check if the fee math here is right
+      debug_msg!("changing assets_from_liqor");
+      assets_from_liqor = I80F48::from_num(max_col)
+        .safe_div(asset_col_price.safe_mul(I80F48::from_num(1f64 + liq_fee))?)?;
+      debug_msg!("assets_from_liqor_is_now_max_col: {}", assets_from_liqor);
+    }
+
+    debug_msg!("assets_from_liqor {:?}", assets_from_liqor);
+
+    //mutate margins
+    liqee_margin.load_mut()?.zod_mutate(
+      -assets_from_liqor,
+      zod_state.load()?.get_borrow_multiplier()?,
+    )?;
+    zod_state
+      .load_mut()?
+      .mutate_zod_borrowed(-assets_from_liqor)?;
+    let liqee_stored = liqee_margin.load_mut()?.mutate(
+      col_index,
+      -I80F48::from_num(col_to_liqor),
+      supply_multiplier,
+      borrow_multiplier,
+    )?;
+    let liqor_stored = liqor_margin.load_mut()?.mutate(
+      col_index,
+      I80F48::from_num(col_to_liqor),
+      supply_multiplier,
+      borrow_multiplier,
+    )?;
+    zod_state
+      .load_mut()?
+      .mutate_total_collateral(col_index, liqee_stored.safe_add(liqor_stored)?)?;
+
+    let assets_from_liqor_u64 = assets_from_liqor.floor().to_num::<u64>();
+    total_zod_burned = total_zod_burned.safe_add(assets_from_liqor_u64)?;
+
+    // whatever was seized beyond the zod burned, at the collateral price, is the liquidator's fee
+    let fee = I80F48::from_num(col_to_liqor)
+      .safe_sub(I80F48::from_num(assets_from_liqor_u64).safe_mul(asset_col_price)?)?
+      .max(I80F48::ZERO)
+      .floor()
+      .to_num::<u64>();
+
+    emit!(ZodLiquidated {
+      liqor: cx.accounts.liqor.key(),
+      liqee,
+      collateral_index: col_index as u8,
+      zod_burned: assets_from_liqor_u64,
+      collateral_seized: col_to_liqor as u64,
+      fee,
+    });
+  }
 
-  msg!(
-    "bob zod token account amount before {:?}",
+  debug_msg!(
+    "liqor zod token account amount before {:?}",
     cx.accounts.token_account.amount
   );
   let burn_cpi_program = cx.accounts.token_program.to_account_info();
@@ -197,32 +279,23 @@ pub fn process(
 
   let burn_cpi_ctx = CpiContext::new(burn_cpi_program, burn_cpi_accounts);
 
-  let assets_from_liqor_u64 = assets_from_liqor.floor().to_num::<u64>();
-
-  token::burn(burn_cpi_ctx, assets_from_liqor_u64)?;
-
-  liqor_margin.load_mut()?.mutate(
-    quote_col_index,
-    I80F48::from_num(quote_to_liqor),
-    cache.load()?.borrow_cache[quote_col_index]
-      .supply_multiplier
-      .into(),
-    cache.load()?.borrow_cache[quote_col_index]
-      .borrow_multiplier
-      .into(),
-  )?;
-
-  msg!("mf after liquidation:");
-  liqee_margin.load()?.get_imf(&zod_state.load()?)?;
-  liqee_margin.load()?.get_mmf(&zod_state.load()?)?;
-  liqee_margin.load()?.get_omf(
-    &zo_program_state,
-    &cache.load()?,
-    &zod_state.load()?,
-    true,
-    current_time,
-    _mock_col_price,
-  )?;
+  token::burn(burn_cpi_ctx, total_zod_burned)?;
+
+  #[cfg(feature = "verbose-logs")]
+  {
+    debug_msg!("mf after liquidation:");
+    liqee_margin.load()?.get_imf(&zod_state.load()?)?;
+    liqee_margin.load()?.get_mmf(&zod_state.load()?)?;
+    liqee_margin.load()?.get_omf(
+      &zo_program_state,
+      &cache.load()?,
+      &zod_state.load()?,
+      cx.remaining_accounts,
+      None,
+      true,
+      current_time,
+    )?;
+  }
 
   Ok(())
 }
@@ -6,6 +6,9 @@ use common::{system_program_utils, time};
 use fixed::types::I80F48;
 use zo::config::SPOT_INITIAL_MARGIN_REQ;
 
+use crate::errors::ZodError;
+use crate::events::ZodMinted;
+use crate::log::debug_msg;
 use crate::state::*;
 use crate::zodTypes::WrappedI80F48;
 use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
@@ -15,7 +18,7 @@ use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
 pub struct ZodMint<'info> {
   #[account(
     mut,
-    seeds = [b"zodv12".as_ref()],
+    seeds = [b"zodv13".as_ref()],
     bump = zod_state.load()?.zod_state_nonce,
   )]
   pub zod_state: AccountLoader<'info, ZodState>,
@@ -33,6 +36,11 @@ pub struct ZodMint<'info> {
     bump = zod_margin.load()?.nonce
   )]
   pub zod_margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    seeds = [zod_state.key().as_ref(), b"zodoraclehistoryv1".as_ref()],
+    bump = oracle_history.load()?.nonce,
+  )]
+  pub oracle_history: AccountLoader<'info, ZodOracleHistory>,
   #[account(
     mut,
     constraint = {token_account.owner == *authority.key},
@@ -49,35 +57,66 @@ pub struct ZodMint<'info> {
 pub fn process(cx: Context<ZodMint>, amount: u64) -> ProgramResult {
   msg!("Instruction ZodMint");
 
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Mint),
+    ZodError::ActionPaused
+  );
+
   let mut zod_margin = cx.accounts.zod_margin.load_mut()?;
   let zod_state = &cx.accounts.zod_state;
   let zo_program_state = &cx.accounts.zo_program_state;
   let cache = &cx.accounts.cache;
   let current_time = time::get_current_time()?;
+
+  require!(
+    zod_margin.is_valid_zod_token_account(
+      &cx.accounts.token_account.key(),
+      &cx.accounts.mint.key()
+    ),
+    ZodError::InvalidTokenAccount
+  );
+
   let amount_i80: I80F48 = I80F48::from_num(amount);
 
-  zod_margin.zod_mutate(amount_i80, zod_state.load()?.soc_loss_multiplier.into())?;
+  zod_state.load_mut()?.accrue_stability_fee(current_time)?;
+  zod_state.load_mut()?.accrue_supply_yield(&cache.load()?)?;
+
+  zod_margin.zod_mutate(amount_i80, zod_state.load()?.get_borrow_multiplier()?)?;
   zod_state.load_mut()?.mutate_zod_borrowed(amount_i80)?;
 
+  require!(
+    zod_state.load()?.get_total_zod_debt()? <= zod_state.load()?.zod_debt_ceiling,
+    ZodError::DebtCeilingExceeded
+  );
+
   let zod_balance: I80F48 =
-    zod_margin.get_actual_zod_balance(zod_state.load()?.soc_loss_multiplier.into())?;
+    zod_margin.get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+
+  zod_margin.check_oracle_deviation(
+    &zo_program_state.load()?,
+    &cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    current_time,
+  )?;
 
   let omf = zod_margin.get_omf(
     &zo_program_state.load()?,
     &cache.load()?,
     &zod_state.load()?,
+    cx.remaining_accounts,
+    Some(&cx.accounts.oracle_history.load()?),
     true,
     current_time,
-    None,
   )?;
 
   let imf = zod_margin.get_imf(&zod_state.load()?)?;
 
-  assert!(omf > imf);
+  require!(omf > imf, ZodError::InsufficientMargin);
 
-  msg!(
+  debug_msg!(
     "total_zod_borrowed after: {}",
-    zod_state.load()?.get_actual_zod_borrowed()
+    zod_state.load()?.get_actual_zod_borrowed()?
   );
 
   let cpi_program = cx.accounts.token_program.to_account_info();
@@ -87,7 +126,7 @@ pub fn process(cx: Context<ZodMint>, amount: u64) -> ProgramResult {
     authority: cx.accounts.zod_state.to_account_info(),
   };
 
-  let zod_state_seeds = &[b"zodv12".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
 
   let signers = &[&zod_state_seeds[..]];
 
@@ -95,5 +134,12 @@ pub fn process(cx: Context<ZodMint>, amount: u64) -> ProgramResult {
 
   token::mint_to(cpi_ctx, amount)?;
 
+  emit!(ZodMinted {
+    authority: cx.accounts.authority.key(),
+    margin: cx.accounts.zod_margin.key(),
+    amount,
+    zod_balance: zod_balance.floor().to_num::<u64>(),
+  });
+
   Ok(())
 }
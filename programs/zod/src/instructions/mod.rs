@@ -1,23 +1,101 @@
+pub mod accept_admin;
 pub mod add_insurance;
 pub mod add_vaults;
+pub mod bid_auction;
 pub mod burn;
+pub mod cancel_insurance_withdrawal;
+pub mod claim_insurance;
+pub mod close_margin;
+pub mod collect_fees;
+pub mod collect_psm_fees;
+pub mod collect_stability_fees;
 pub mod create_margin;
 pub mod deposit;
+pub mod drip_savings;
+pub mod end_liquidation_auction;
+pub mod flash_mint;
+pub mod flash_repay;
+pub mod init_insurance_shares;
+pub mod init_oracle_history;
+pub mod init_psm;
+pub mod init_savings;
 pub mod init_state;
 pub mod liquidate;
 pub mod mint;
+pub mod propose_admin;
+pub mod psm_swap_in;
+pub mod psm_swap_out;
+pub mod redeem;
 pub mod reduce_insurance;
+pub mod request_insurance_withdrawal;
+pub mod savings_deposit;
+pub mod savings_withdraw;
+pub mod set_admin_timelock;
+pub mod set_collateral_cap;
+pub mod set_debt_ceiling;
+pub mod set_flash_mint_fee;
+pub mod set_guardian;
+pub mod set_insurance_cooldown;
+pub mod set_min_insurance_ratio;
+pub mod set_oracle_guards;
+pub mod set_oracle_source;
+pub mod set_pause_flags;
+pub mod set_reserve_ratio;
+pub mod set_stability_fee;
 pub mod settle_bankruptcy;
+pub mod start_liquidation_auction;
+pub mod update_oracle_history;
+pub mod update_psm;
+pub mod update_zod_params;
 pub mod withdraw;
 
+pub use accept_admin::*;
 pub use add_insurance::*;
 pub use add_vaults::*;
+pub use bid_auction::*;
 pub use burn::*;
+pub use cancel_insurance_withdrawal::*;
+pub use claim_insurance::*;
+pub use close_margin::*;
+pub use collect_fees::*;
+pub use collect_psm_fees::*;
+pub use collect_stability_fees::*;
 pub use create_margin::*;
 pub use deposit::*;
+pub use drip_savings::*;
+pub use end_liquidation_auction::*;
+pub use flash_mint::*;
+pub use flash_repay::*;
+pub use init_insurance_shares::*;
+pub use init_oracle_history::*;
+pub use init_psm::*;
+pub use init_savings::*;
 pub use init_state::*;
 pub use liquidate::*;
 pub use mint::*;
+pub use propose_admin::*;
+pub use psm_swap_in::*;
+pub use psm_swap_out::*;
+pub use redeem::*;
 pub use reduce_insurance::*;
+pub use request_insurance_withdrawal::*;
+pub use savings_deposit::*;
+pub use savings_withdraw::*;
+pub use set_admin_timelock::*;
+pub use set_collateral_cap::*;
+pub use set_debt_ceiling::*;
+pub use set_flash_mint_fee::*;
+pub use set_guardian::*;
+pub use set_insurance_cooldown::*;
+pub use set_min_insurance_ratio::*;
+pub use set_oracle_guards::*;
+pub use set_oracle_source::*;
+pub use set_pause_flags::*;
+pub use set_reserve_ratio::*;
+pub use set_stability_fee::*;
 pub use settle_bankruptcy::*;
+pub use start_liquidation_auction::*;
+pub use update_oracle_history::*;
+pub use update_psm::*;
+pub use update_zod_params::*;
 pub use withdraw::*;
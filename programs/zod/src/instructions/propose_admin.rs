@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use common::{time, SafeOp};
+
+use crate::events::AdminProposed;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(new_admin: Pubkey)]
+pub struct ProposeAdmin<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+// proposing the default pubkey cancels a pending transfer
+pub fn process(cx: Context<ProposeAdmin>, new_admin: Pubkey) -> ProgramResult {
+  msg!("Instruction: ProposeAdmin");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let current_time = time::get_current_time()?;
+
+  zod_state.pending_admin = new_admin;
+  zod_state.pending_admin_eta = current_time.safe_add(zod_state.admin_timelock)?;
+
+  emit!(AdminProposed {
+    admin: zod_state.admin,
+    pending_admin: new_admin,
+    eta: zod_state.pending_admin_eta,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::bps::get_bps;
+use common::{system_program_utils, SafeOp};
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::PsmSwapped;
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct PsmSwapIn<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    constraint = {token_account.owner == *authority.key},
+    constraint = token_account.mint == zod_state.load()?.psm_mint,
+    constraint = token_account.amount >= amount,
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  #[account(mut, address = zod_state.load()?.psm_vault)]
+  pub psm_vault: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = zod_token_account.mint == mint.key(),
+  )]
+  pub zod_token_account: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<PsmSwapIn>, amount: u64) -> ProgramResult {
+  msg!("Instruction: PsmSwapIn");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Psm),
+    ZodError::ActionPaused
+  );
+
+  let zod_out: u64;
+  let fee: u64;
+  {
+    let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+    require!(zod_state.is_psm_initialized(), ZodError::PsmNotInitialized);
+
+    fee = I80F48::from_num(amount)
+      .safe_mul(get_bps(zod_state.psm_tin))?
+      .ceil()
+      .to_num::<u64>();
+    zod_out = amount.safe_sub(fee)?;
+
+    let new_debt = { zod_state.psm_debt }.safe_add(zod_out)?;
+    require!(
+      new_debt <= zod_state.psm_debt_ceiling,
+      ZodError::PsmDebtCeilingExceeded
+    );
+
+    zod_state.psm_debt = new_debt;
+    require!(
+      zod_state.get_total_zod_debt()? <= zod_state.zod_debt_ceiling,
+      ZodError::DebtCeilingExceeded
+    );
+    zod_state.psm_fees_accrued = { zod_state.psm_fees_accrued }.safe_add(fee)?;
+    debug_msg!("psm fee: {}, zod out: {}", fee, zod_out);
+  }
+
+  system_program_utils::token_transfer(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.psm_vault.to_account_info(),
+    cx.accounts.authority.to_account_info(),
+    amount,
+    None,
+  )?;
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signers = &[&zod_state_seeds[..]];
+
+  system_program_utils::mint_to(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.mint.to_account_info(),
+    cx.accounts.zod_token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    zod_out,
+    Some(signers),
+  )?;
+
+  emit!(PsmSwapped {
+    authority: cx.accounts.authority.key(),
+    is_swap_in: true,
+    amount_in: amount,
+    amount_out: zod_out,
+    fee,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use common::bps::get_bps;
+use common::{system_program_utils, SafeOp};
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::PsmSwapped;
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct PsmSwapOut<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    constraint = {zod_token_account.owner == *authority.key},
+    constraint = zod_token_account.mint == mint.key(),
+    constraint = zod_token_account.amount >= amount,
+  )]
+  pub zod_token_account: Account<'info, TokenAccount>,
+  #[account(mut, address = zod_state.load()?.psm_vault)]
+  pub psm_vault: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = token_account.mint == zod_state.load()?.psm_mint,
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<PsmSwapOut>, amount: u64) -> ProgramResult {
+  msg!("Instruction: PsmSwapOut");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Psm),
+    ZodError::ActionPaused
+  );
+
+  let usdc_out: u64;
+  let fee: u64;
+  {
+    let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+    require!(zod_state.is_psm_initialized(), ZodError::PsmNotInitialized);
+    require!(amount <= zod_state.psm_debt, ZodError::PsmInsufficientReserve);
+
+    fee = I80F48::from_num(amount)
+      .safe_mul(get_bps(zod_state.psm_tout))?
+      .ceil()
+      .to_num::<u64>();
+    usdc_out = amount.safe_sub(fee)?;
+
+    // the fee stays in the vault, so the reserve still covers psm_debt + psm_fees_accrued
+    zod_state.psm_debt = { zod_state.psm_debt }.safe_sub(amount)?;
+    zod_state.psm_fees_accrued = { zod_state.psm_fees_accrued }.safe_add(fee)?;
+    debug_msg!("psm fee: {}, usdc out: {}", fee, usdc_out);
+  }
+
+  let burn_cpi_program = cx.accounts.token_program.to_account_info();
+  let burn_cpi_accounts = Burn {
+    mint: cx.accounts.mint.to_account_info(),
+    to: cx.accounts.zod_token_account.to_account_info(),
+    authority: cx.accounts.authority.to_account_info(),
+  };
+
+  let burn_cpi_ctx = CpiContext::new(burn_cpi_program, burn_cpi_accounts);
+
+  token::burn(burn_cpi_ctx, amount)?;
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signer = &[&zod_state_seeds[..]];
+
+  system_program_utils::token_transfer(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.psm_vault.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    usdc_out,
+    Some(signer),
+  )?;
+
+  emit!(PsmSwapped {
+    authority: cx.accounts.authority.key(),
+    is_swap_in: false,
+    amount_in: amount,
+    amount_out: usdc_out,
+    fee,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, *};
+use common::{time, SafeOp};
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::events::ZodRedeemed;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct RedeemZod<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(
+    constraint = {zo_program_state.key() == zod_state.load()?.zo_program_state},
+  )]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  pub redeemer: Signer<'info>,
+  #[account(
+    mut,
+    seeds = [redeemer.key.as_ref(), zod_state.key().as_ref(), b"zodmarginv2".as_ref()],
+    bump = redeemer_zod_margin.load()?.nonce
+  )]
+  pub redeemer_zod_margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    mut,
+    seeds = [target_zod_margin.load()?.authority.as_ref(), zod_state.key().as_ref(), b"zodmarginv2".as_ref()],
+    bump = target_zod_margin.load()?.nonce
+  )]
+  pub target_zod_margin: AccountLoader<'info, ZodMargin>,
+  pub collateral_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = {zod_mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub zod_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = {token_account.owner == *redeemer.key},
+    constraint = token_account.amount >= amount,
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+  // remaining accounts: other zod margins the target must be at least as risky as,
+  // plus the oracle accounts of collaterals not priced from the 01 cache
+}
+
+pub fn process<'info>(cx: Context<'_, '_, '_, 'info, RedeemZod<'info>>, amount: u64) -> ProgramResult {
+  msg!("Instruction: RedeemZod");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Redeem),
+    ZodError::ActionPaused
+  );
+
+  let zo_program_state = cx.accounts.zo_program_state.load()?;
+  let zod_state = &cx.accounts.zod_state;
+  let cache = &cx.accounts.cache;
+  let target_margin = &cx.accounts.target_zod_margin;
+  let redeemer_margin = &cx.accounts.redeemer_zod_margin;
+  let current_time = time::get_current_time()?;
+
+  zod_state.load_mut()?.accrue_stability_fee(current_time)?;
+  zod_state.load_mut()?.accrue_supply_yield(&cache.load()?)?;
+
+  let col_index = zo_program_state
+    .get_collateral_index(cx.accounts.collateral_mint.to_account_info().key)
+    .ok_or(ZodError::InvalidMint)?;
+
+  // there is no on-chain ordering of margins, so the caller supplies the candidates it
+  // found off-chain and the target has to be the riskiest among them. healthy margins can be
+  // redeemed against too, that is what keeps a floor under the peg. the target loses no value,
+  // its debt drops by the full amount while the collateral it gives up is worth the amount minus the fee
+  let target_ratio = target_margin.load()?.get_collateral_ratio(
+    &zo_program_state,
+    &cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    current_time,
+  )?;
+  debug_msg!("target collateral ratio: {}", target_ratio);
+
+  for acc in cx.remaining_accounts.iter() {
+    // oracles share the list, some of them owned by this program too
+    if acc.key() == target_margin.key() || !is_zod_margin(acc, cx.program_id) {
+      continue;
+    }
+    let candidate = AccountLoader::<ZodMargin>::try_from(acc)?;
+    let candidate_ratio = candidate.load()?.get_collateral_ratio(
+      &zo_program_state,
+      &cache.load()?,
+      &zod_state.load()?,
+      cx.remaining_accounts,
+      current_time,
+    )?;
+    require!(
+      target_ratio <= candidate_ratio,
+      ZodError::RedemptionTargetNotRiskiest
+    );
+  }
+
+  let amount_i80: I80F48 = I80F48::from_num(amount);
+  let zod_balance: I80F48 = target_margin
+    .load()?
+    .get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+  require!(amount_i80 <= zod_balance, ZodError::RedemptionExceedsDebt);
+
+  let fee_rate = zod_state
+    .load_mut()?
+    .register_redemption(amount_i80, current_time)?;
+  debug_msg!("redemption fee rate: {}", fee_rate);
+
+  let col_price: I80F48 = PriceReader::new(
+    &zo_program_state,
+    &cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    current_time,
+  )?
+  .get_price(col_index)?
+  .price;
+
+  // the fee is left with the redeemed margin as collateral
+  let col_to_redeemer = amount_i80
+    .safe_mul(I80F48::ONE.safe_sub(fee_rate)?)?
+    .safe_div(col_price)?
+    .floor();
+
+  let supply_multiplier: I80F48 = zod_state.load()?.get_supply_multiplier(col_index);
+  let borrow_multiplier: I80F48 = cache.load()?.borrow_cache[col_index].borrow_multiplier.into();
+
+  let target_col = target_margin
+    .load()?
+    .get_actual_collateral(col_index, supply_multiplier)?;
+  require!(
+    col_to_redeemer <= target_col,
+    ZodError::RedemptionCollateralInsufficient
+  );
+  debug_msg!("collateral to redeemer: {}", col_to_redeemer);
+
+  target_margin
+    .load_mut()?
+    .zod_mutate(-amount_i80, zod_state.load()?.get_borrow_multiplier()?)?;
+  zod_state.load_mut()?.mutate_zod_borrowed(-amount_i80)?;
+  let target_stored = target_margin.load_mut()?.mutate(
+    col_index,
+    -col_to_redeemer,
+    supply_multiplier,
+    borrow_multiplier,
+  )?;
+  let redeemer_stored = redeemer_margin.load_mut()?.mutate(
+    col_index,
+    col_to_redeemer,
+    supply_multiplier,
+    borrow_multiplier,
+  )?;
+  zod_state
+    .load_mut()?
+    .mutate_total_collateral(col_index, target_stored.safe_add(redeemer_stored)?)?;
+
+  let burn_cpi_program = cx.accounts.token_program.to_account_info();
+  let burn_cpi_accounts = Burn {
+    mint: cx.accounts.zod_mint.to_account_info(),
+    to: cx.accounts.token_account.to_account_info(),
+    authority: cx.accounts.redeemer.to_account_info(),
+  };
+
+  let burn_cpi_ctx = CpiContext::new(burn_cpi_program, burn_cpi_accounts);
+
+  token::burn(burn_cpi_ctx, amount)?;
+
+  emit!(ZodRedeemed {
+    redeemer: cx.accounts.redeemer.key(),
+    target: target_margin.load()?.authority,
+    collateral_index: col_index as u8,
+    zod_burned: amount,
+    collateral_out: col_to_redeemer.to_num::<u64>(),
+    fee_rate: fee_rate.into(),
+  });
+
+  Ok(())
+}
+
+fn is_zod_margin(acc: &AccountInfo, program_id: &Pubkey) -> bool {
+  acc.owner == program_id
+    && acc
+      .try_borrow_data()
+      .map(|data| data.len() >= 8 && data[..8] == ZodMargin::discriminator())
+      .unwrap_or(false)
+}
@@ -1,108 +1,128 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::log::sol_log_compute_units;
-use anchor_spl::token::{Token, TokenAccount, *};
-use common::SafeOp;
-use common::{system_program_utils, time};
-use fixed::types::I80F48;
-use zo::config::SPOT_INITIAL_MARGIN_REQ;
+use anchor_spl::token::{self, Token, TokenAccount, *};
+use common::{time, SafeOp};
 
+use crate::errors::ZodError;
+use crate::events::{InsuranceChanged, InsuranceSharesBurned};
+use crate::log::debug_msg;
 use crate::state::*;
-use crate::zodTypes::WrappedI80F48;
 
-use ::zo::cpi::accounts::Withdraw;
-use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
-use zo::errors::ErrorCode;
+use zo::{self, *};
 
 #[derive(Accounts)]
-#[instruction(amount: u64)]
+#[instruction(shares: u64)]
 pub struct ZodReduceInsurance<'info> {
-  #[account(mut)]
-  pub zo_program_margin: AccountLoader<'info, Margin>,
-  pub zo_program: Program<'info, Zo>,
-  #[account(mut)]
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
   pub zod_state: AccountLoader<'info, ZodState>,
-  #[account(mut)]
+  #[account(address = zod_state.load()?.zo_program_state)]
   pub zo_program_state: AccountLoader<'info, State>,
-  #[account(mut)]
-  pub state_signer: UncheckedAccount<'info>,
-  #[account(mut, address = zo_program_state.load()?.cache)]
+  #[account(address = zo_program_state.load()?.cache)]
   pub cache: AccountLoader<'info, Cache>,
-  #[account(address = zod_state.load()?.admin)]
-  pub admin: Signer<'info>,
-  #[account(mut, address = zo_program_margin.load()?.control)]
-  pub control: AccountLoader<'info, Control>,
+  pub authority: Signer<'info>,
   #[account(
     mut,
-    constraint = {msg!("test1"); token_account.owner == *admin.key},
+    address = zod_state.load()?.insurance_share_mint,
   )]
-  pub token_account: Account<'info, TokenAccount>,
+  pub share_mint: Account<'info, Mint>,
   #[account(
     mut,
-    constraint = {msg!("test2"); zo_vault.owner == *state_signer.to_account_info().key},
-    constraint = zo_vault.mint == token_account.mint
+    seeds = [authority.key.as_ref(), zod_state.key().as_ref(), b"zodinsurancewithdrawv1".as_ref()],
+    bump,
   )]
-  pub zo_vault: Account<'info, TokenAccount>,
+  pub withdrawal: Account<'info, ZodInsuranceWithdrawal>,
   #[account(
     mut,
-    constraint = {msg!("test3"); zod_vault.owner == *zod_state.to_account_info().key},
-    constraint = zod_vault.mint == token_account.mint
+    seeds = [withdrawal.key().as_ref(), b"zodinsuranceescrowv1".as_ref()],
+    bump,
   )]
-  pub zod_vault: Account<'info, TokenAccount>,
+  pub escrow: Account<'info, TokenAccount>,
   pub token_program: Program<'info, Token>,
 }
 
-pub fn process(cx: Context<ZodReduceInsurance>, amount: u64) -> ProgramResult {
+pub fn process(cx: Context<ZodReduceInsurance>, shares: u64) -> ProgramResult {
   msg!("Instruction: ZodReduceInsurance");
 
-  let zo_program_state = &cx.accounts.zo_program_state;
-  let zod_state = &cx.accounts.zod_state;
-  let token_acc = &cx.accounts.token_account;
-
-  let col_index = zo_program_state
-    .load()?
-    .get_collateral_index(&token_acc.mint)
-    .ok_or(ErrorCode::CollateralDoesNotExist)?;
-  assert!(zo_program_state.load()?.vaults[col_index] == cx.accounts.zo_vault.key());
-  assert!(col_index == 0);
-
-  let amount_i80: I80F48 = I80F48::from_num(amount);
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Insurance),
+    ZodError::ActionPaused
+  );
 
+  let zod_state = &cx.accounts.zod_state;
+  let current_time = time::get_current_time()?;
+  let withdrawal = &mut cx.accounts.withdrawal;
+  require!(shares <= withdrawal.shares, ZodError::InsufficientInsuranceShares);
   {
-    zod_state.load_mut()?.mutate_insurance(-(amount as i64))?;
+    let zod_state = zod_state.load()?;
+    let opens_at = withdrawal.requested_at.saturating_add(zod_state.insurance_cooldown);
+    require!(current_time >= opens_at, ZodError::InsuranceCooldownNotElapsed);
+    require!(
+      current_time <= opens_at.saturating_add(zod_state.insurance_withdraw_window),
+      ZodError::InsuranceWithdrawWindowExpired
+    );
   }
+  withdrawal.shares -= shares;
 
-  let amount_to_withdraw: u64 = amount_i80.floor().to_num();
-
-  let zod_state_seeds = &[b"zodv12".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  // shares are paid out as the same slice of every collateral in the fund, so no one can pick the
+  // best assets and leave the rest to the other holders. losses paid out of insurance in
+  // settle_bankruptcy already lowered every slice. the collateral is claimed with claim_insurance
+  let mut payouts = [0u64; 25];
+  {
+    let state = cx.accounts.zo_program_state.load()?;
+    let cache = cx.accounts.cache.load()?;
+    let mut zod_state = zod_state.load_mut()?;
+    zod_state.accrue_stability_fee(current_time)?;
+
+    let share_supply = cx.accounts.share_mint.supply;
+    for i in 0..state.total_collaterals as usize {
+      let amount = zod_state.get_insurance_payout(i, shares, share_supply)?;
+      if amount == 0 {
+        continue;
+      }
+      debug_msg!("insurance withdrawal collateral: {}, amount: {}", i, amount);
+      zod_state.mutate_insurance(i, -(amount as i64))?;
+      withdrawal.owed[i] = withdrawal.owed[i].safe_add(amount)?;
+      payouts[i] = amount;
+    }
+
+    require!(
+      zod_state.get_insurance_value(&state, &cache, cx.remaining_accounts, current_time)?
+        >= zod_state.get_min_insurance()?,
+      ZodError::InsuranceBelowFloor
+    );
+  }
 
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
   let signer = &[&zod_state_seeds[..]];
 
-  let cpi_program = cx.accounts.zo_program.to_account_info();
-
-  let cpi_accounts = Withdraw {
-    state: cx.accounts.zo_program_state.to_account_info(),
-    state_signer: cx.accounts.state_signer.to_account_info(),
-    cache: cx.accounts.cache.to_account_info(),
+  let burn_cpi_program = cx.accounts.token_program.to_account_info();
+  let burn_cpi_accounts = Burn {
+    mint: cx.accounts.share_mint.to_account_info(),
+    to: cx.accounts.escrow.to_account_info(),
     authority: cx.accounts.zod_state.to_account_info(),
-    margin: cx.accounts.zo_program_margin.to_account_info(),
-    control: cx.accounts.control.to_account_info(),
-    token_account: cx.accounts.zod_vault.to_account_info(),
-    vault: cx.accounts.zo_vault.to_account_info(),
-    token_program: cx.accounts.token_program.to_account_info(),
   };
-
-  let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-
-  zo::cpi::withdraw(cpi_ctx, false, amount)?;
-
-  system_program_utils::token_transfer(
-    cx.accounts.token_program.to_account_info().clone(),
-    cx.accounts.zod_vault.to_account_info().clone(),
-    cx.accounts.token_account.to_account_info().clone(),
-    cx.accounts.zod_state.to_account_info().clone(),
-    amount_to_withdraw,
-    Some(signer),
-  )?;
+  let burn_cpi_ctx = CpiContext::new_with_signer(burn_cpi_program, burn_cpi_accounts, signer);
+  token::burn(burn_cpi_ctx, shares)?;
+
+  emit!(InsuranceSharesBurned {
+    authority: cx.accounts.authority.key(),
+    shares,
+  });
+  for (i, amount) in payouts.iter().enumerate() {
+    if *amount == 0 {
+      continue;
+    }
+    emit!(InsuranceChanged {
+      authority: cx.accounts.authority.key(),
+      collateral_index: i as u8,
+      amount: -(*amount as i64),
+      shares: 0,
+      insurance: zod_state.load()?.insurance[i],
+    });
+  }
 
   Ok(())
 }
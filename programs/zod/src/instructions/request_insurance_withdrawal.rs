@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use common::{time, SafeOp};
+use std::mem::size_of;
+
+use crate::errors::ZodError;
+use crate::events::InsuranceWithdrawalRequested;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(nonce: u8, shares: u64)]
+pub struct RequestInsuranceWithdrawal<'info> {
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(address = zod_state.load()?.insurance_share_mint)]
+  pub share_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = {share_account.owner == *authority.key},
+    constraint = share_account.mint == share_mint.key(),
+  )]
+  pub share_account: Account<'info, TokenAccount>,
+  #[account(
+    init_if_needed,
+    seeds = [authority.key.as_ref(), zod_state.key().as_ref(), b"zodinsurancewithdrawv1".as_ref()],
+    bump = nonce,
+    payer = authority,
+    space = 8 + size_of::<ZodInsuranceWithdrawal>()
+  )]
+  pub withdrawal: Account<'info, ZodInsuranceWithdrawal>,
+  // holds the requested shares until reduce_insurance burns them
+  #[account(
+    init_if_needed,
+    seeds = [withdrawal.key().as_ref(), b"zodinsuranceescrowv1".as_ref()],
+    bump,
+    payer = authority,
+    token::mint = share_mint,
+    token::authority = zod_state,
+  )]
+  pub escrow: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+  pub system_program: Program<'info, System>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn process(cx: Context<RequestInsuranceWithdrawal>, _nonce: u8, shares: u64) -> ProgramResult {
+  msg!("Instruction: RequestInsuranceWithdrawal");
+
+  let zod_state = cx.accounts.zod_state.load()?;
+  require!(
+    zod_state.is_insurance_shares_initialized(),
+    ZodError::InsuranceSharesNotInitialized
+  );
+  require!(
+    cx.accounts.share_account.amount >= shares,
+    ZodError::InsufficientInsuranceShares
+  );
+
+  // escrowed shares can't be moved to another account and requested again while this one cools down
+  let cpi_program = cx.accounts.token_program.to_account_info();
+  let cpi_accounts = Transfer {
+    from: cx.accounts.share_account.to_account_info(),
+    to: cx.accounts.escrow.to_account_info(),
+    authority: cx.accounts.authority.to_account_info(),
+  };
+  token::transfer(CpiContext::new(cpi_program, cpi_accounts), shares)?;
+
+  // a new request adds to the escrowed shares and restarts the cooldown for all of them
+  let current_time = time::get_current_time()?;
+  let withdrawal = &mut cx.accounts.withdrawal;
+  withdrawal.authority = cx.accounts.authority.key();
+  withdrawal.shares = withdrawal.shares.safe_add(shares)?;
+  withdrawal.requested_at = current_time;
+
+  emit!(InsuranceWithdrawalRequested {
+    authority: withdrawal.authority,
+    shares: withdrawal.shares,
+    unlock_time: current_time.saturating_add(zod_state.insurance_cooldown),
+  });
+
+  Ok(())
+}
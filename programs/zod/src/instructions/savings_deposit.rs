@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::{system_program_utils, time, SafeOp};
+
+use crate::errors::ZodError;
+use crate::events::SavingsChanged;
+use crate::log::debug_msg;
+use crate::instructions::drip_savings::drip;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct SavingsDeposit<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    constraint = {token_account.owner == *authority.key},
+    constraint = token_account.mint == zod_state.load()?.zod_token_info.mint,
+    constraint = token_account.amount >= amount,
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  #[account(mut, address = zod_state.load()?.savings_vault)]
+  pub savings_vault: Account<'info, TokenAccount>,
+  #[account(mut, address = zod_state.load()?.savings_mint)]
+  pub savings_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = share_account.mint == savings_mint.key(),
+  )]
+  pub share_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<SavingsDeposit>, amount: u64) -> ProgramResult {
+  msg!("Instruction: SavingsDeposit");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Savings),
+    ZodError::ActionPaused
+  );
+  require!(
+    cx.accounts.zod_state.load()?.is_savings_initialized(),
+    ZodError::SavingsNotInitialized
+  );
+
+  // yield kept up to now is dripped first and priced against the vault before this deposit, so it
+  // stays with earlier holders instead of being shared with whoever deposits right before a drip
+  let dripped = drip(
+    &cx.accounts.zod_state,
+    &cx.accounts.zo_program_state,
+    &cx.accounts.cache,
+    &cx.accounts.mint,
+    &cx.accounts.savings_vault,
+    &cx.accounts.token_program,
+    cx.remaining_accounts,
+    time::get_current_time()?,
+    true,
+  )?;
+  let vault_balance = cx.accounts.savings_vault.amount.safe_add(dripped)?;
+  let shares =
+    ZodState::get_savings_shares(amount, vault_balance, cx.accounts.savings_mint.supply)?;
+  debug_msg!("savings deposit: {}, shares: {}", amount, shares);
+  require!(shares > 0, ZodError::SavingsAmountTooSmall);
+
+  system_program_utils::token_transfer(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.savings_vault.to_account_info(),
+    cx.accounts.authority.to_account_info(),
+    amount,
+    None,
+  )?;
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signers = &[&zod_state_seeds[..]];
+
+  system_program_utils::mint_to(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.savings_mint.to_account_info(),
+    cx.accounts.share_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    shares,
+    Some(signers),
+  )?;
+
+  emit!(SavingsChanged {
+    authority: cx.accounts.authority.key(),
+    amount: amount as i64,
+    shares: shares as i64,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use common::{system_program_utils, time, SafeOp};
+
+use crate::errors::ZodError;
+use crate::events::SavingsChanged;
+use crate::log::debug_msg;
+use crate::instructions::drip_savings::drip;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(shares: u64)]
+pub struct SavingsWithdraw<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(
+    mut,
+    constraint = {mint.key() == zod_state.load()?.zod_token_info.mint},
+  )]
+  pub mint: Account<'info, Mint>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    constraint = token_account.mint == zod_state.load()?.zod_token_info.mint,
+  )]
+  pub token_account: Account<'info, TokenAccount>,
+  #[account(mut, address = zod_state.load()?.savings_vault)]
+  pub savings_vault: Account<'info, TokenAccount>,
+  #[account(mut, address = zod_state.load()?.savings_mint)]
+  pub savings_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    constraint = {share_account.owner == *authority.key},
+    constraint = share_account.mint == savings_mint.key(),
+    constraint = share_account.amount >= shares,
+  )]
+  pub share_account: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+}
+
+pub fn process(cx: Context<SavingsWithdraw>, shares: u64) -> ProgramResult {
+  msg!("Instruction: SavingsWithdraw");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Savings),
+    ZodError::ActionPaused
+  );
+
+  // yield kept up to now is paid out to the shares being burned too
+  let dripped = drip(
+    &cx.accounts.zod_state,
+    &cx.accounts.zo_program_state,
+    &cx.accounts.cache,
+    &cx.accounts.mint,
+    &cx.accounts.savings_vault,
+    &cx.accounts.token_program,
+    cx.remaining_accounts,
+    time::get_current_time()?,
+    true,
+  )?;
+  let vault_balance = cx.accounts.savings_vault.amount.safe_add(dripped)?;
+  let amount =
+    ZodState::get_savings_amount(shares, vault_balance, cx.accounts.savings_mint.supply)?;
+  debug_msg!("savings withdrawal shares: {}, amount: {}", shares, amount);
+  require!(amount > 0, ZodError::SavingsAmountTooSmall);
+
+  let burn_cpi_program = cx.accounts.token_program.to_account_info();
+  let burn_cpi_accounts = Burn {
+    mint: cx.accounts.savings_mint.to_account_info(),
+    to: cx.accounts.share_account.to_account_info(),
+    authority: cx.accounts.authority.to_account_info(),
+  };
+  let burn_cpi_ctx = CpiContext::new(burn_cpi_program, burn_cpi_accounts);
+  token::burn(burn_cpi_ctx, shares)?;
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[cx.accounts.zod_state.load()?.zod_state_nonce]];
+  let signers = &[&zod_state_seeds[..]];
+
+  system_program_utils::token_transfer(
+    cx.accounts.token_program.to_account_info(),
+    cx.accounts.savings_vault.to_account_info(),
+    cx.accounts.token_account.to_account_info(),
+    cx.accounts.zod_state.to_account_info(),
+    amount,
+    Some(signers),
+  )?;
+
+  emit!(SavingsChanged {
+    authority: cx.accounts.authority.key(),
+    amount: -(amount as i64),
+    shares: -(shares as i64),
+  });
+
+  Ok(())
+}
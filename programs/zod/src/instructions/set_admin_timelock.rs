@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZodError;
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(delay: u64)]
+pub struct SetAdminTimelock<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+// only applies to transfers proposed after this call. the delay can only grow, otherwise a stolen
+// admin key could drop it to zero and propose and accept a new admin in the same transaction
+pub fn process(cx: Context<SetAdminTimelock>, delay: u64) -> ProgramResult {
+  msg!("Instruction: SetAdminTimelock");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  require!(delay >= zod_state.admin_timelock, ZodError::AdminTimelockDecrease);
+  zod_state.admin_timelock = delay;
+
+  debug_msg!("admin timelock: {}", delay);
+
+  Ok(())
+}
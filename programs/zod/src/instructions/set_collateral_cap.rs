@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZodError;
+use crate::events::CollateralCapUpdated;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(collateral_index: u8, cap: u64)]
+pub struct SetCollateralCap<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+}
+
+pub fn process(cx: Context<SetCollateralCap>, collateral_index: u8, cap: u64) -> ProgramResult {
+  msg!("Instruction: SetCollateralCap");
+
+  let index = collateral_index as usize;
+  require!(
+    index < cx.accounts.zo_program_state.load()?.total_collaterals as usize,
+    ZodError::CollateralDoesNotExist
+  );
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let old_cap = zod_state.collateral_caps[index];
+  zod_state.collateral_caps[index] = cap;
+
+  emit!(CollateralCapUpdated {
+    collateral_index,
+    old_cap,
+    new_cap: cap,
+  });
+
+  Ok(())
+}
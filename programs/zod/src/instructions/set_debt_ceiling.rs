@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::events::DebtCeilingUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(ceiling: u64)]
+pub struct SetDebtCeiling<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetDebtCeiling>, ceiling: u64) -> ProgramResult {
+  msg!("Instruction: SetDebtCeiling");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let old_ceiling = zod_state.zod_debt_ceiling;
+  zod_state.zod_debt_ceiling = ceiling;
+
+  emit!(DebtCeilingUpdated {
+    old_ceiling,
+    new_ceiling: ceiling,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZodError;
+use crate::events::FlashMintFeeUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(fee: u16)]
+pub struct SetFlashMintFee<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetFlashMintFee>, fee: u16) -> ProgramResult {
+  msg!("Instruction: SetFlashMintFee");
+
+  require!(fee <= 10_000, ZodError::InvalidFee);
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let old_fee = zod_state.flash_mint_fee;
+  zod_state.flash_mint_fee = fee;
+
+  emit!(FlashMintFeeUpdated {
+    old_fee,
+    new_fee: fee,
+  });
+
+  Ok(())
+}
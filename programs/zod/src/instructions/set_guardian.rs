@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(guardian: Pubkey)]
+pub struct SetGuardian<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetGuardian>, guardian: Pubkey) -> ProgramResult {
+  msg!("Instruction: SetGuardian");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  zod_state.guardian = guardian;
+
+  debug_msg!("guardian: {}", guardian);
+
+  Ok(())
+}
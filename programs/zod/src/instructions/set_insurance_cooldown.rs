@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(cooldown: u64, withdraw_window: u64)]
+pub struct SetInsuranceCooldown<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetInsuranceCooldown>, cooldown: u64, withdraw_window: u64) -> ProgramResult {
+  msg!("Instruction: SetInsuranceCooldown");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  zod_state.insurance_cooldown = cooldown;
+  zod_state.insurance_withdraw_window = withdraw_window;
+
+  Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::events::MinInsuranceRatioUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(ratio: u16)]
+pub struct SetMinInsuranceRatio<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetMinInsuranceRatio>, ratio: u16) -> ProgramResult {
+  msg!("Instruction: SetMinInsuranceRatio");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let old_ratio = zod_state.min_insurance_ratio;
+  zod_state.min_insurance_ratio = ratio;
+
+  emit!(MinInsuranceRatioUpdated {
+    old_ratio,
+    new_ratio: ratio,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZodError;
+use crate::events::OracleGuardsUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(max_confidence: u16, max_deviation: u16)]
+pub struct SetOracleGuards<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetOracleGuards>, max_confidence: u16, max_deviation: u16) -> ProgramResult {
+  msg!("Instruction: SetOracleGuards");
+
+  require!(
+    max_confidence <= 10_000 && max_deviation <= 10_000,
+    ZodError::InvalidFee
+  );
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  zod_state.max_oracle_confidence = max_confidence;
+  zod_state.max_oracle_deviation = max_deviation;
+
+  emit!(OracleGuardsUpdated {
+    max_confidence,
+    max_deviation,
+  });
+
+  Ok(())
+}
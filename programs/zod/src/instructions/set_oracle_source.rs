@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use common::time;
+use std::convert::TryFrom;
+
+use crate::errors::ZodError;
+use crate::events::OracleSourceUpdated;
+use crate::log::debug_msg;
+use crate::oracle::{OracleSource, PriceReader, ZodOracleConfig};
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(collateral_index: u8, source: u8, deviation_source: u8)]
+pub struct SetOracleSource<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  // pyth price account or switchboard aggregator, ignored when switching back to the 01 cache
+  pub oracle: UncheckedAccount<'info>,
+  // same for the source mints and withdrawals check the price against
+  pub deviation_oracle: UncheckedAccount<'info>,
+}
+
+pub fn process(
+  cx: Context<SetOracleSource>,
+  collateral_index: u8,
+  source: u8,
+  deviation_source: u8,
+) -> ProgramResult {
+  msg!("Instruction: SetOracleSource");
+
+  let index = collateral_index as usize;
+  let zo_program_state = cx.accounts.zo_program_state.load()?;
+  require!(
+    index < zo_program_state.total_collaterals as usize
+      && !zo_program_state.collaterals[index].is_empty(),
+    ZodError::CollateralDoesNotExist
+  );
+
+  let oracle = match OracleSource::try_from(source)? {
+    OracleSource::ZoCache => Pubkey::default(),
+    _ => cx.accounts.oracle.key(),
+  };
+  let deviation_oracle = match OracleSource::try_from(deviation_source)? {
+    OracleSource::ZoCache => Pubkey::default(),
+    _ => cx.accounts.deviation_oracle.key(),
+  };
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  zod_state.oracle_configs[index] = ZodOracleConfig {
+    source,
+    account: oracle,
+    deviation_source,
+    deviation_account: deviation_oracle,
+  };
+
+  // the new sources have to give usable, agreeing prices right away, which also checks the accounts parse
+  let oracles = &[
+    cx.accounts.oracle.to_account_info(),
+    cx.accounts.deviation_oracle.to_account_info(),
+  ];
+  let prices = PriceReader::new(
+    &zo_program_state,
+    &cx.accounts.cache.load()?,
+    zod_state,
+    oracles,
+    time::get_current_time()?,
+  )?;
+  let price = prices.get_price(index)?;
+  prices.check_deviation(index)?;
+  debug_msg!("new oracle price: {}", price.price);
+
+  emit!(OracleSourceUpdated {
+    collateral_index,
+    source,
+    oracle,
+    deviation_source,
+    deviation_oracle,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZodError;
+use crate::events::PauseFlagsUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(flags: u16)]
+pub struct SetPauseFlags<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(
+    constraint = {
+      authority.key() == zod_state.load()?.admin || authority.key() == zod_state.load()?.guardian
+    },
+  )]
+  pub authority: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetPauseFlags>, flags: u16) -> ProgramResult {
+  msg!("Instruction: SetPauseFlags");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let old_flags = zod_state.pause_flags;
+
+  // the guardian can only pause more actions, lifting a pause is left to the admin
+  if cx.accounts.authority.key() != zod_state.admin {
+    require!(flags & old_flags == old_flags, ZodError::GuardianCannotUnpause);
+  }
+
+  zod_state.pause_flags = flags;
+
+  emit!(PauseFlagsUpdated {
+    authority: cx.accounts.authority.key(),
+    old_flags,
+    new_flags: flags,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use common::SafeOp;
+
+use crate::errors::ZodError;
+use crate::events::ReserveRatioUpdated;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(ratio: u16)]
+pub struct SetReserveRatio<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+}
+
+pub fn process(cx: Context<SetReserveRatio>, ratio: u16) -> ProgramResult {
+  msg!("Instruction: SetReserveRatio");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  require!(
+    { zod_state.savings_yield_share }.safe_add(ratio)? <= 10_000,
+    ZodError::InvalidFee
+  );
+
+  // yield up to now is split at the old ratio
+  zod_state.accrue_supply_yield(&cx.accounts.cache.load()?)?;
+
+  let old_ratio = zod_state.reserve_ratio;
+  zod_state.reserve_ratio = ratio;
+
+  emit!(ReserveRatioUpdated {
+    old_ratio,
+    new_ratio: ratio,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use common::bps::get_bps;
+use common::{time, SafeOp};
+use fixed::types::I80F48;
+
+use crate::errors::ZodError;
+use crate::log::debug_msg;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(annual_rate_bps: u16)]
+pub struct SetStabilityFee<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<SetStabilityFee>, annual_rate_bps: u16) -> ProgramResult {
+  msg!("Instruction: SetStabilityFee");
+
+  require!(annual_rate_bps <= 10_000, ZodError::InvalidRate);
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let current_time = time::get_current_time()?;
+
+  // settle fees owed at the old rate before switching over
+  zod_state.accrue_stability_fee(current_time)?;
+
+  let rate_per_second = get_bps(annual_rate_bps).safe_div(I80F48::from_num(SECONDS_PER_YEAR))?;
+  zod_state.stability_fee_rate = rate_per_second.into();
+
+  debug_msg!("stability fee rate per second: {}", rate_per_second);
+
+  Ok(())
+}
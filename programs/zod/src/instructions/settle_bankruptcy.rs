@@ -6,6 +6,10 @@ use common::{system_program_utils, time};
 use fixed::types::I80F48;
 use zo::config::{SPOT_INITIAL_MARGIN_REQ, SPOT_MAINT_MARGIN_REQ};
 
+use crate::errors::ZodError;
+use crate::events::BankruptcySettled;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
 use crate::state::*;
 use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
 
@@ -38,7 +42,6 @@ pub struct SettleZodBankruptcy<'info> {
     constraint = {zod_mint.key() == zod_state.load()?.zod_token_info.mint},
   )]
   pub zod_mint: Account<'info, Mint>,
-  pub quote_mint: Account<'info, Mint>,
   #[account(
     mut,
     constraint = {token_account.owner == *liqor.key},
@@ -47,9 +50,13 @@ pub struct SettleZodBankruptcy<'info> {
   pub token_program: Program<'info, Token>,
 }
 
-pub fn process(cx: Context<SettleZodBankruptcy>, _mock_col_price: Option<u64>) -> ProgramResult {
-  #[cfg(feature = "devnet")]
-  msg!("mock collateral price: {:?}", _mock_col_price);
+pub fn process(cx: Context<SettleZodBankruptcy>) -> ProgramResult {
+  msg!("Instruction: SettleZodBankruptcy");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::SettleBankruptcy),
+    ZodError::ActionPaused
+  );
 
   let mut zod_state = cx.accounts.zod_state.load_mut()?;
   let zo_program_state = cx.accounts.zo_program_state.load_mut()?;
@@ -58,21 +65,25 @@ pub fn process(cx: Context<SettleZodBankruptcy>, _mock_col_price: Option<u64>) -
   let liqor_margin = &cx.accounts.liqor_zod_margin;
   let current_time = Clock::get()?.unix_timestamp as u64;
 
+  zod_state.accrue_stability_fee(current_time)?;
+  zod_state.accrue_supply_yield(&cache.load()?)?;
+
   let below_dust = liqee_margin.load()?.has_no_col_above_dust(
-    &zo_program_state.collaterals,
-    zo_program_state.total_collaterals as usize,
+    &zo_program_state,
     &cache.load()?,
+    &zod_state,
+    cx.remaining_accounts,
     current_time,
-    _mock_col_price,
   )?;
 
-  msg!("below_dust: {:?}", below_dust);
+  debug_msg!("below_dust: {:?}", below_dust);
 
-  assert!(below_dust);
+  require!(below_dust, ZodError::NotBankrupt);
 
+  let liqee = liqee_margin.load()?.authority;
   let assets_from_liqor = liqee_margin
     .load()?
-    .get_actual_zod_balance(zod_state.soc_loss_multiplier.into())?;
+    .get_actual_zod_balance(zod_state.get_borrow_multiplier()?)?;
 
   let pre_fee_quote = (assets_from_liqor).floor().to_num::<i64>();
 
@@ -83,7 +94,7 @@ pub fn process(cx: Context<SettleZodBankruptcy>, _mock_col_price: Option<u64>) -
   //do mutations
   liqee_margin.load_mut()?.bankrupt()?;
 
-  zod_state.mutate_zod_borrowed(assets_from_liqor)?;
+  zod_state.mutate_zod_borrowed(-assets_from_liqor)?;
   let burn_cpi_program = cx.accounts.token_program.to_account_info();
   let burn_cpi_accounts = Burn {
     mint: cx.accounts.zod_mint.to_account_info(),
@@ -95,41 +106,84 @@ pub fn process(cx: Context<SettleZodBankruptcy>, _mock_col_price: Option<u64>) -
 
   let assets_to_liqor_u64: u64 = assets_from_liqor.to_num::<u64>();
 
-  msg!("assets_to_liqor_u64: {}", assets_to_liqor_u64);
+  debug_msg!("assets_to_liqor_u64: {}", assets_to_liqor_u64);
 
   token::burn(burn_cpi_ctx, assets_to_liqor_u64)?;
 
-  liqor_margin.load_mut()?.mutate(
-    0,
-    I80F48::from_num(quote_to_liqor),
-    cache.load()?.borrow_cache[0].supply_multiplier.into(),
-    cache.load()?.borrow_cache[0].borrow_multiplier.into(),
-  )?;
+  debug_msg!("quote_to_liqor {}", quote_to_liqor);
+
+  // pay the liqor out of the insurance basket, quote first, at unweighted oracle prices
+  let mut owed = I80F48::from_num(quote_to_liqor); // in smol usd
+  for i in 0..zo_program_state.total_collaterals as usize {
+    if owed < I80F48::ONE {
+      break;
+    }
+    let balance = zod_state.insurance[i];
+    if balance == 0 {
+      continue;
+    }
+
+    let price = PriceReader::new(
+      &zo_program_state,
+      &cache.load()?,
+      &zod_state,
+      cx.remaining_accounts,
+      current_time,
+    )?
+    .get_price(i)?
+    .price;
+    let paid = owed
+      .safe_div(price)?
+      .ceil()
+      .min(I80F48::from_num(balance));
+    zod_state.mutate_insurance(i, -paid.to_num::<i64>())?;
+    let stored = liqor_margin.load_mut()?.mutate(
+      i,
+      paid,
+      zod_state.get_supply_multiplier(i),
+      cache.load()?.borrow_cache[i].borrow_multiplier.into(),
+    )?;
+    zod_state.mutate_total_collateral(i, stored)?;
+    owed = owed.safe_sub(paid.safe_mul(price)?)?.max(I80F48::ZERO);
+    debug_msg!("Insurance refunded {} of collateral {}", paid, i);
+  }
 
-  msg!("quote_to_liqor {}", quote_to_liqor);
+  let owed = owed.floor().to_num::<i64>();
+  let insurance_used: i64 = quote_to_liqor - owed;
+  let mut socialized_amount: i64 = 0;
 
-  // check max insurance fund amount
-  if quote_to_liqor > zod_state.insurance as i64 {
-    // socialize losses
-    let insurance = zod_state.insurance as i64;
-    zod_state.mutate_insurance(-insurance)?;
-    msg!("Insurance refunded {}", insurance);
+  // whatever the insurance fund could not cover is paid in quote and socialized
+  if owed > 0 {
+    let stored = liqor_margin.load_mut()?.mutate(
+      0,
+      I80F48::from_num(owed),
+      zod_state.get_supply_multiplier(0),
+      cache.load()?.borrow_cache[0].borrow_multiplier.into(),
+    )?;
+    zod_state.mutate_total_collateral(0, stored)?;
 
     // for every dollar supplied, socialize loss
-    let zod_borrowed = zod_state.get_actual_zod_borrowed();
-    msg!("insurance {}", insurance);
-    msg!("zod_borrowed {}", zod_borrowed);
-    let socialize_amount: I80F48 = I80F48::from_num(quote_to_liqor - insurance) / zod_borrowed;
-    require!(socialize_amount < I80F48::ONE, MathFailure);
+    let zod_borrowed = zod_state.get_actual_zod_borrowed()?;
+    debug_msg!("zod_borrowed {}", zod_borrowed);
+    let socialize_amount: I80F48 = I80F48::from_num(owed).safe_div(zod_borrowed)?;
+    require!(socialize_amount < I80F48::ONE, ZodError::SocializedLossTooLarge);
 
     // decrease supply multiplier
 
     zod_state.socialize_loss(socialize_amount)?;
-    msg!("Socialized loss of {}", socialize_amount);
-  } else {
-    zod_state.mutate_insurance(-quote_to_liqor)?;
-    msg!("Insurance refunded {}", quote_to_liqor);
+    debug_msg!("Socialized loss of {}", socialize_amount);
+
+    socialized_amount = owed;
   }
 
+  emit!(BankruptcySettled {
+    liqor: cx.accounts.liqor.key(),
+    liqee,
+    zod_burned: assets_to_liqor_u64,
+    insurance_used: insurance_used as u64,
+    socialized_amount: socialized_amount as u64,
+    soc_loss_multiplier: I80F48::from(zod_state.soc_loss_multiplier).into(),
+  });
+
   Ok(())
 }
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use common::time;
+use std::mem::size_of;
+
+use crate::errors::ZodError;
+use crate::events::LiquidationAuctionStarted;
+use crate::log::debug_msg;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+#[instruction(nonce: u8)]
+pub struct StartLiquidationAuction<'info> {
+  #[account(mut)]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(
+    constraint = {zo_program_state.key() == zod_state.load()?.zo_program_state},
+  )]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(mut)]
+  pub keeper: Signer<'info>,
+  #[account(
+    seeds = [liqee_zod_margin.load()?.authority.as_ref(), zod_state.key().as_ref(), b"zodmarginv2".as_ref()],
+    bump = liqee_zod_margin.load()?.nonce
+  )]
+  pub liqee_zod_margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    init,
+    seeds = [liqee_zod_margin.key().as_ref(), zod_state.key().as_ref(), b"zodauctionv1".as_ref()],
+    bump = nonce,
+    payer = keeper,
+    space = 8 + size_of::<ZodLiquidationAuction>()
+  )]
+  pub auction: AccountLoader<'info, ZodLiquidationAuction>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn process(
+  cx: Context<StartLiquidationAuction>,
+  nonce: u8,
+) -> ProgramResult {
+  msg!("Instruction: StartLiquidationAuction");
+
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Liquidate),
+    ZodError::ActionPaused
+  );
+
+  let zod_state = &cx.accounts.zod_state;
+  let liqee_margin = cx.accounts.liqee_zod_margin.load()?;
+  let current_time = time::get_current_time()?;
+
+  zod_state.load_mut()?.accrue_stability_fee(current_time)?;
+  zod_state.load_mut()?.accrue_supply_yield(&cx.accounts.cache.load()?)?;
+
+  let omf = liqee_margin.get_omf(
+    &cx.accounts.zo_program_state.load()?,
+    &cx.accounts.cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    None,
+    true,
+    current_time,
+  )?;
+  let mmf = liqee_margin.get_mmf(&zod_state.load()?)?;
+  debug_msg!("omf: {}, mmf: {}", omf, mmf);
+
+  require!(omf < mmf, ZodError::NotLiquidatable);
+
+  let auction = &mut cx.accounts.auction.load_init()?;
+  auction.nonce = nonce;
+  auction.margin = cx.accounts.liqee_zod_margin.key();
+  auction.keeper = cx.accounts.keeper.key();
+  auction.start_time = current_time;
+  auction.zod_repaid = 0;
+  auction.is_restored = 0;
+
+  emit!(LiquidationAuctionStarted {
+    keeper: auction.keeper,
+    liqee: liqee_margin.authority,
+    auction: cx.accounts.auction.key(),
+    start_time: current_time,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use common::time;
+
+use crate::events::OracleHistoryUpdated;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
+use crate::state::*;
+use zo::{self, *};
+
+#[derive(Accounts)]
+pub struct UpdateOracleHistory<'info> {
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.zo_program_state)]
+  pub zo_program_state: AccountLoader<'info, State>,
+  #[account(address = zo_program_state.load()?.cache)]
+  pub cache: AccountLoader<'info, Cache>,
+  #[account(
+    mut,
+    seeds = [zod_state.key().as_ref(), b"zodoraclehistoryv1".as_ref()],
+    bump = oracle_history.load()?.nonce,
+  )]
+  pub oracle_history: AccountLoader<'info, ZodOracleHistory>,
+}
+
+pub fn process(cx: Context<UpdateOracleHistory>) -> ProgramResult {
+  msg!("Instruction: UpdateOracleHistory");
+
+  let current_time = time::get_current_time()?;
+  let zo_program_state = cx.accounts.zo_program_state.load()?;
+  let cache = cx.accounts.cache.load()?;
+  let zod_state = cx.accounts.zod_state.load()?;
+  let history = &mut cx.accounts.oracle_history.load_mut()?;
+
+  let prices = PriceReader::new(
+    &zo_program_state,
+    &cache,
+    &zod_state,
+    cx.remaining_accounts,
+    current_time,
+  )?;
+
+  // permissionless crank, collaterals without a readable price just keep their previous average
+  for i in 0..zo_program_state.total_collaterals as usize {
+    if zo_program_state.collaterals[i].is_empty() {
+      continue;
+    }
+    let spot = match prices.get_price(i) {
+      Ok(price) => price.price,
+      Err(_) => continue,
+    };
+    history.update(i, spot, current_time)?;
+    debug_msg!("collateral {} spot: {}, ema: {}", i, spot, { history.ema[i] });
+  }
+
+  emit!(OracleHistoryUpdated {
+    ema: history.ema,
+  });
+
+  Ok(())
+}
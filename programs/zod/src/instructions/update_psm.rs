@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZodError;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(tin: u16, tout: u16, debt_ceiling: u64)]
+pub struct UpdatePsm<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(cx: Context<UpdatePsm>, tin: u16, tout: u16, debt_ceiling: u64) -> ProgramResult {
+  msg!("Instruction: UpdatePsm");
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+
+  require!(zod_state.is_psm_initialized(), ZodError::PsmNotInitialized);
+  require!(tin <= 10_000 && tout <= 10_000, ZodError::InvalidFee);
+
+  // lowering the ceiling below the current debt only blocks new swaps in
+  zod_state.psm_tin = tin;
+  zod_state.psm_tout = tout;
+  zod_state.psm_debt_ceiling = debt_ceiling;
+
+  Ok(())
+}
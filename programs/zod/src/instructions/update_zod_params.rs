@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use zo::config::{SPOT_INITIAL_MARGIN_REQ, SPOT_MAINT_MARGIN_REQ};
+
+use crate::errors::ZodError;
+use crate::events::ZodParamsUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct UpdateZodParams<'info> {
+  #[account(
+    mut,
+    seeds = [b"zodv13".as_ref()],
+    bump = zod_state.load()?.zod_state_nonce,
+  )]
+  pub zod_state: AccountLoader<'info, ZodState>,
+  #[account(address = zod_state.load()?.admin)]
+  pub admin: Signer<'info>,
+}
+
+pub fn process(
+  cx: Context<UpdateZodParams>,
+  weight: u16,
+  liq_fee: u16,
+  optimal_util: u16,
+  optimal_rate: u16,
+  max_rate: u16,
+  og_fee: u16,
+) -> ProgramResult {
+  msg!("Instruction: UpdateZodParams");
+
+  // get_imf and get_mmf charge REQ / weight - 1000 per zod, so both have to stay positive,
+  // and get_max_reducible needs the initial requirement to exceed the liquidation fee
+  require!(weight > 0 && weight <= 1000, ZodError::InvalidWeight);
+  let base_imf = SPOT_INITIAL_MARGIN_REQ as u32 / weight as u32;
+  let base_mmf = SPOT_MAINT_MARGIN_REQ as u32 / weight as u32;
+  require!(base_mmf > 1000 && base_imf > 1000, ZodError::InvalidWeight);
+  require!(base_imf - 1000 > liq_fee as u32, ZodError::InvalidWeight);
+
+  require!(optimal_util <= 1000, ZodError::InvalidRate);
+  require!(optimal_rate <= max_rate, ZodError::InvalidRate);
+  require!(og_fee <= 10_000, ZodError::InvalidFee);
+
+  let zod_state = &mut cx.accounts.zod_state.load_mut()?;
+  let old = zod_state.zod_token_info;
+
+  zod_state.zod_token_info.weight = weight;
+  zod_state.zod_token_info.liq_fee = liq_fee;
+  zod_state.zod_token_info.optimal_util = optimal_util;
+  zod_state.zod_token_info.optimal_rate = optimal_rate;
+  zod_state.zod_token_info.max_rate = max_rate;
+  zod_state.zod_token_info.og_fee = og_fee;
+
+  emit!(ZodParamsUpdated {
+    old_weight: old.weight,
+    new_weight: weight,
+    old_liq_fee: old.liq_fee,
+    new_liq_fee: liq_fee,
+    old_optimal_util: old.optimal_util,
+    new_optimal_util: optimal_util,
+    old_optimal_rate: old.optimal_rate,
+    new_optimal_rate: optimal_rate,
+    old_max_rate: old.max_rate,
+    new_max_rate: max_rate,
+    old_og_fee: old.og_fee,
+    new_og_fee: og_fee,
+  });
+
+  Ok(())
+}
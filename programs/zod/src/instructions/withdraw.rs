@@ -6,9 +6,11 @@ use common::{system_program_utils, time};
 use fixed::types::I80F48;
 use zo::config::SPOT_INITIAL_MARGIN_REQ;
 
+use crate::errors::ZodError;
+use crate::events::ZodWithdrawn;
+use crate::log::debug_msg;
 use crate::state::*;
 use crate::zodTypes::WrappedI80F48;
-use zo::errors::ErrorCode;
 use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
 
 use ::zo::cpi::accounts::Withdraw;
@@ -35,6 +37,11 @@ pub struct ZodWithdraw<'info> {
     bump = zod_margin.load()?.nonce
   )]
   pub zod_margin: AccountLoader<'info, ZodMargin>,
+  #[account(
+    seeds = [zod_state.key().as_ref(), b"zodoraclehistoryv1".as_ref()],
+    bump = oracle_history.load()?.nonce,
+  )]
+  pub oracle_history: AccountLoader<'info, ZodOracleHistory>,
   #[account(mut, address = zo_program_margin.load()?.control)]
   pub control: AccountLoader<'info, Control>,
   #[account(
@@ -65,6 +72,11 @@ pub struct ZodWithdraw<'info> {
 pub fn process(cx: Context<ZodWithdraw>, amount: u64) -> ProgramResult {
   msg!("Instruction: ZodWithdraw");
 
+  require!(
+    !cx.accounts.zod_state.load()?.is_paused(ZodAction::Withdraw),
+    ZodError::ActionPaused
+  );
+
   let zo_program_state = &cx.accounts.zo_program_state;
   let zod_state = &cx.accounts.zod_state;
   let token_acc = &cx.accounts.token_account;
@@ -73,54 +85,69 @@ pub fn process(cx: Context<ZodWithdraw>, amount: u64) -> ProgramResult {
   let zod_token_acc = &cx.accounts.zod_account;
   let current_time = time::get_current_time()?;
 
+  zod_state.load_mut()?.accrue_stability_fee(current_time)?;
+  zod_state.load_mut()?.accrue_supply_yield(&cache.load()?)?;
+
   let col_index = zo_program_state
     .load()?
     .get_collateral_index(&token_acc.mint)
-    .ok_or(ErrorCode::CollateralDoesNotExist)?;
-  assert!(zo_program_state.load()?.vaults[col_index] == cx.accounts.zo_vault.key());
+    .ok_or(ZodError::CollateralDoesNotExist)?;
+  require!(
+    zo_program_state.load()?.vaults[col_index] == cx.accounts.zo_vault.key(),
+    ZodError::InvalidVault
+  );
 
-  let actual_col = zod_margin.load()?.get_actual_collateral(
-    col_index,
-    cache.load()?.borrow_cache[col_index]
-      .supply_multiplier
-      .into(),
-  )?;
+  let actual_col = zod_margin
+    .load()?
+    .get_actual_collateral(col_index, zod_state.load()?.get_supply_multiplier(col_index))?;
 
   let amount_i80: I80F48 = I80F48::from_num(amount);
-  assert!(actual_col > amount_i80);
+  require!(actual_col > amount_i80, ZodError::InsufficientCollateral);
 
-  zod_margin.load_mut()?.mutate(
+  let stored = zod_margin.load_mut()?.mutate(
     col_index,
     -amount_i80,
-    cache.load()?.borrow_cache[col_index]
-      .supply_multiplier
-      .into(),
+    zod_state.load()?.get_supply_multiplier(col_index),
     cache.load()?.borrow_cache[col_index]
       .borrow_multiplier
       .into(),
   )?;
+  zod_state.load_mut()?.mutate_total_collateral(col_index, stored)?;
 
   let zod_balance: I80F48 = zod_margin
   .load()?
-  .get_actual_zod_balance(zod_state.load()?.soc_loss_multiplier.into())?;
-  msg!("zod_balance: {}", zod_balance);
+  .get_actual_zod_balance(zod_state.load()?.get_borrow_multiplier()?)?;
+  debug_msg!("zod_balance: {}", zod_balance);
+
+  zod_margin.load()?.check_oracle_deviation(
+    &zo_program_state.load()?,
+    &cache.load()?,
+    &zod_state.load()?,
+    cx.remaining_accounts,
+    current_time,
+  )?;
 
   let omf = zod_margin.load()?.get_omf(
     &zo_program_state.load()?,
     &cache.load()?,
     &zod_state.load()?,
+    cx.remaining_accounts,
+    Some(&cx.accounts.oracle_history.load()?),
     true,
     current_time,
-    None,
   )?;
 
   let imf = zod_margin.load()?.get_imf(&zod_state.load()?)?;
 
-  assert!(omf > imf);
+  require!(omf > imf, ZodError::InsufficientMargin);
 
   let amount_to_withdraw: u64 = amount_i80.floor().to_num();
 
-  let zod_state_seeds = &[b"zodv12".as_ref(), &[zod_state.load()?.zod_state_nonce]];
+  zod_state
+    .load_mut()?
+    .mutate_total_deposits(col_index, -(amount as i64))?;
+
+  let zod_state_seeds = &[b"zodv13".as_ref(), &[zod_state.load()?.zod_state_nonce]];
 
   let signer = &[&zod_state_seeds[..]];
 
@@ -151,5 +178,12 @@ pub fn process(cx: Context<ZodWithdraw>, amount: u64) -> ProgramResult {
     Some(signer),
   )?;
 
+  emit!(ZodWithdrawn {
+    authority: cx.accounts.authority.key(),
+    margin: cx.accounts.zod_margin.key(),
+    collateral_index: col_index as u8,
+    amount: amount_to_withdraw,
+  });
+
   Ok(())
 }
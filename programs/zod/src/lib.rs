@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
 
+pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod log;
+pub mod oracle;
 pub mod state;
 pub mod zodTypes;
 
 use instructions::*;
-use zo::errors::ErrorCode;
 
 declare_id!("HjBqgYKdav882K1bbnoaSr3QmZ9mxQcpmAFrvrAKjrpL");
 
@@ -24,6 +27,13 @@ pub mod zod {
         instructions::create_margin::process(cx, nonce)
     }
 
+    pub fn close_zod_margin(
+        cx: Context<CloseZodMargin>,
+        close_token_account: bool,
+    ) -> ProgramResult {
+        instructions::close_margin::process(cx, close_token_account)
+    }
+
     pub fn add_vaults(cx: Context<AddVaults>) -> ProgramResult {
         instructions::add_vaults::process(cx)
     }
@@ -44,19 +54,222 @@ pub mod zod {
         instructions::burn::process(cx, amount)
     }
 
-    pub fn liquidate_zod_position(cx: Context<LiquidateZodPosition>, amount: u64, _mock_col_price: Option<u64>) -> ProgramResult {
-        instructions::liquidate::process(cx, amount, _mock_col_price)
+    pub fn liquidate_zod_position(
+        cx: Context<LiquidateZodPosition>,
+        legs: Vec<LiquidationLeg>,
+    ) -> ProgramResult {
+        instructions::liquidate::process(cx, legs)
+    }
+
+    pub fn start_liquidation_auction(cx: Context<StartLiquidationAuction>, nonce: u8) -> ProgramResult {
+        instructions::start_liquidation_auction::process(cx, nonce)
+    }
+
+    pub fn bid_auction(
+        cx: Context<BidAuction>,
+        collateral_index: u8,
+        amount: u64,
+    ) -> ProgramResult {
+        instructions::bid_auction::process(cx, collateral_index, amount)
+    }
+
+    pub fn end_liquidation_auction(cx: Context<EndLiquidationAuction>) -> ProgramResult {
+        instructions::end_liquidation_auction::process(cx)
     }
 
     pub fn zod_add_insurance(cx: Context<ZodAddInsurance>, amount: u64) -> ProgramResult {
         instructions::add_insurance::process(cx, amount)
     }
 
-    pub fn zod_reduce_insurance(cx: Context<ZodReduceInsurance>, amount: u64) -> ProgramResult {
-        instructions::reduce_insurance::process(cx, amount)
+    pub fn zod_reduce_insurance(cx: Context<ZodReduceInsurance>, shares: u64) -> ProgramResult {
+        instructions::reduce_insurance::process(cx, shares)
+    }
+
+    pub fn claim_insurance(cx: Context<ClaimInsurance>) -> ProgramResult {
+        instructions::claim_insurance::process(cx)
+    }
+
+    pub fn request_insurance_withdrawal(
+        cx: Context<RequestInsuranceWithdrawal>,
+        nonce: u8,
+        shares: u64,
+    ) -> ProgramResult {
+        instructions::request_insurance_withdrawal::process(cx, nonce, shares)
+    }
+
+    pub fn cancel_insurance_withdrawal(
+        cx: Context<CancelInsuranceWithdrawal>,
+        shares: u64,
+    ) -> ProgramResult {
+        instructions::cancel_insurance_withdrawal::process(cx, shares)
+    }
+
+    pub fn zod_settle_bankruptcy(cx: Context<SettleZodBankruptcy>) -> ProgramResult {
+        instructions::settle_bankruptcy::process(cx)
+    }
+
+    pub fn set_stability_fee(cx: Context<SetStabilityFee>, annual_rate_bps: u16) -> ProgramResult {
+        instructions::set_stability_fee::process(cx, annual_rate_bps)
+    }
+
+    pub fn collect_stability_fees(cx: Context<CollectStabilityFees>) -> ProgramResult {
+        instructions::collect_stability_fees::process(cx)
+    }
+
+    pub fn init_psm(cx: Context<InitPsm>, tin: u16, tout: u16, debt_ceiling: u64) -> ProgramResult {
+        instructions::init_psm::process(cx, tin, tout, debt_ceiling)
+    }
+
+    pub fn update_psm(cx: Context<UpdatePsm>, tin: u16, tout: u16, debt_ceiling: u64) -> ProgramResult {
+        instructions::update_psm::process(cx, tin, tout, debt_ceiling)
+    }
+
+    pub fn psm_swap_in(cx: Context<PsmSwapIn>, amount: u64) -> ProgramResult {
+        instructions::psm_swap_in::process(cx, amount)
+    }
+
+    pub fn psm_swap_out(cx: Context<PsmSwapOut>, amount: u64) -> ProgramResult {
+        instructions::psm_swap_out::process(cx, amount)
+    }
+
+    pub fn collect_psm_fees(cx: Context<CollectPsmFees>) -> ProgramResult {
+        instructions::collect_psm_fees::process(cx)
+    }
+
+    pub fn redeem_zod<'info>(cx: Context<'_, '_, '_, 'info, RedeemZod<'info>>, amount: u64) -> ProgramResult {
+        instructions::redeem::process(cx, amount)
+    }
+
+    pub fn update_zod_params(
+        cx: Context<UpdateZodParams>,
+        weight: u16,
+        liq_fee: u16,
+        optimal_util: u16,
+        optimal_rate: u16,
+        max_rate: u16,
+        og_fee: u16,
+    ) -> ProgramResult {
+        instructions::update_zod_params::process(
+            cx,
+            weight,
+            liq_fee,
+            optimal_util,
+            optimal_rate,
+            max_rate,
+            og_fee,
+        )
+    }
+
+    pub fn propose_admin(cx: Context<ProposeAdmin>, new_admin: Pubkey) -> ProgramResult {
+        instructions::propose_admin::process(cx, new_admin)
+    }
+
+    pub fn accept_admin(cx: Context<AcceptAdmin>) -> ProgramResult {
+        instructions::accept_admin::process(cx)
+    }
+
+    pub fn set_admin_timelock(cx: Context<SetAdminTimelock>, delay: u64) -> ProgramResult {
+        instructions::set_admin_timelock::process(cx, delay)
+    }
+
+    pub fn set_pause_flags(cx: Context<SetPauseFlags>, flags: u16) -> ProgramResult {
+        instructions::set_pause_flags::process(cx, flags)
+    }
+
+    pub fn set_guardian(cx: Context<SetGuardian>, guardian: Pubkey) -> ProgramResult {
+        instructions::set_guardian::process(cx, guardian)
+    }
+
+    pub fn set_collateral_cap(cx: Context<SetCollateralCap>, collateral_index: u8, cap: u64) -> ProgramResult {
+        instructions::set_collateral_cap::process(cx, collateral_index, cap)
+    }
+
+    pub fn set_debt_ceiling(cx: Context<SetDebtCeiling>, ceiling: u64) -> ProgramResult {
+        instructions::set_debt_ceiling::process(cx, ceiling)
+    }
+
+    pub fn flash_mint(cx: Context<FlashMint>, amount: u64) -> ProgramResult {
+        instructions::flash_mint::process(cx, amount)
+    }
+
+    pub fn flash_repay(cx: Context<FlashRepay>) -> ProgramResult {
+        instructions::flash_repay::process(cx)
+    }
+
+    pub fn set_flash_mint_fee(cx: Context<SetFlashMintFee>, fee: u16) -> ProgramResult {
+        instructions::set_flash_mint_fee::process(cx, fee)
+    }
+
+    pub fn init_insurance_shares(
+        cx: Context<InitInsuranceShares>,
+        cooldown: u64,
+        withdraw_window: u64,
+    ) -> ProgramResult {
+        instructions::init_insurance_shares::process(cx, cooldown, withdraw_window)
+    }
+
+    pub fn set_insurance_cooldown(
+        cx: Context<SetInsuranceCooldown>,
+        cooldown: u64,
+        withdraw_window: u64,
+    ) -> ProgramResult {
+        instructions::set_insurance_cooldown::process(cx, cooldown, withdraw_window)
+    }
+
+    pub fn set_min_insurance_ratio(cx: Context<SetMinInsuranceRatio>, ratio: u16) -> ProgramResult {
+        instructions::set_min_insurance_ratio::process(cx, ratio)
+    }
+
+    pub fn init_savings(cx: Context<InitSavings>, yield_share: u16) -> ProgramResult {
+        instructions::init_savings::process(cx, yield_share)
+    }
+
+    pub fn savings_deposit(cx: Context<SavingsDeposit>, amount: u64) -> ProgramResult {
+        instructions::savings_deposit::process(cx, amount)
+    }
+
+    pub fn savings_withdraw(cx: Context<SavingsWithdraw>, shares: u64) -> ProgramResult {
+        instructions::savings_withdraw::process(cx, shares)
+    }
+
+    pub fn drip_savings(cx: Context<DripSavings>) -> ProgramResult {
+        instructions::drip_savings::process(cx)
+    }
+
+    pub fn set_reserve_ratio(cx: Context<SetReserveRatio>, ratio: u16) -> ProgramResult {
+        instructions::set_reserve_ratio::process(cx, ratio)
+    }
+
+    pub fn collect_fees(cx: Context<CollectFees>) -> ProgramResult {
+        instructions::collect_fees::process(cx)
+    }
+
+    pub fn set_oracle_source(
+        cx: Context<SetOracleSource>,
+        collateral_index: u8,
+        source: u8,
+        deviation_source: u8,
+    ) -> ProgramResult {
+        instructions::set_oracle_source::process(cx, collateral_index, source, deviation_source)
+    }
+
+    pub fn set_oracle_guards(
+        cx: Context<SetOracleGuards>,
+        max_confidence: u16,
+        max_deviation: u16,
+    ) -> ProgramResult {
+        instructions::set_oracle_guards::process(cx, max_confidence, max_deviation)
+    }
+
+    pub fn init_oracle_history(
+        cx: Context<InitOracleHistory>,
+        nonce: u8,
+        period: u64,
+    ) -> ProgramResult {
+        instructions::init_oracle_history::process(cx, nonce, period)
     }
 
-    pub fn zod_settle_bankruptcy(cx: Context<SettleZodBankruptcy>, _mock_col_price: Option<u64>) -> ProgramResult {
-        instructions::settle_bankruptcy::process(cx, _mock_col_price)
+    pub fn update_oracle_history(cx: Context<UpdateOracleHistory>) -> ProgramResult {
+        instructions::update_oracle_history::process(cx)
     }
 }
@@ -0,0 +1,9 @@
+// msg! costs compute units on every call, so debug output only makes it into
+// builds with the `verbose-logs` feature
+macro_rules! debug_msg {
+  ($($arg:tt)*) => {{
+    #[cfg(feature = "verbose-logs")]
+    anchor_lang::solana_program::msg!($($arg)*);
+  }};
+}
+pub(crate) use debug_msg;
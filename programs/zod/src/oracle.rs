@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use common::bps::get_bps;
+use common::currency::big_price_to_smol_price;
+use common::SafeOp;
+use fixed::types::I80F48;
+use pyth_client::PriceStatus;
+use std::convert::TryFrom;
+use switchboard_program::{get_aggregator, get_aggregator_result};
+use zo::{self, *};
+
+use crate::errors::ZodError;
+use crate::log::debug_msg;
+use crate::state::ZodState;
+#[cfg(feature = "test-oracle")]
+use zod_test_oracle::TestOracle;
+
+// prices are quoted in usd, which 01 accounts for in smol usdc
+pub const USD_DECIMALS: u32 = 6;
+
+// ~25 seconds, pyth and switchboard are read directly so staleness is counted in slots
+pub const MAX_ORACLE_SLOT_AGE: u64 = 50;
+
+// in seconds. checked here rather than with the cache's is_stale, which asserts instead of erroring
+pub const MAX_CACHE_AGE: u64 = 30;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OracleSource {
+    ZoCache = 0,
+    Pyth = 1,
+    Switchboard = 2,
+    #[cfg(feature = "test-oracle")]
+    Test = 3, // account of the zod-test-oracle program, only built with the test-oracle feature
+}
+
+impl TryFrom<u8> for OracleSource {
+    type Error = ProgramError;
+
+    fn try_from(source: u8) -> Result<Self, Self::Error> {
+        match source {
+            0 => Ok(OracleSource::ZoCache),
+            1 => Ok(OracleSource::Pyth),
+            2 => Ok(OracleSource::Switchboard),
+            #[cfg(feature = "test-oracle")]
+            3 => Ok(OracleSource::Test),
+            #[cfg(not(feature = "test-oracle"))]
+            3 => Err(ZodError::TestOracleDisabled.into()),
+            _ => Err(ZodError::InvalidOracleSource.into()),
+        }
+    }
+}
+
+// where zod reads a collateral's price from. zeroed configs read the 01 cache
+#[zero_copy]
+#[derive(Default)]
+pub struct ZodOracleConfig {
+    pub source: u8,
+    pub account: Pubkey, // pyth price account, switchboard aggregator or test oracle, unused for the 01 cache
+
+    // second source the price is checked against before mints and withdrawals, 01 cache disables it
+    pub deviation_source: u8,
+    pub deviation_account: Pubkey,
+}
+
+// in smol usd per smol collateral
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: I80F48,
+    pub confidence: I80F48, // zero for the 01 cache, which does not publish one
+}
+
+impl OraclePrice {
+    // bottom of the confidence interval, what collateral is conservatively worth
+    pub fn get_lower_bound(&self) -> Result<I80F48, ProgramError> {
+        Ok(self.price.safe_sub(self.confidence)?.max(I80F48::ZERO))
+    }
+
+    // errors if the confidence interval is wider than max_confidence bps of the price, zero disables it
+    pub fn check_confidence(&self, max_confidence: u16) -> ProgramResult {
+        require!(
+            max_confidence == 0 || self.confidence <= self.price.safe_mul(get_bps(max_confidence))?,
+            ZodError::OracleConfidenceTooWide
+        );
+        Ok(())
+    }
+}
+
+// prices collaterals through whichever source zod_state configures for them.
+// pyth, switchboard and test oracle accounts are looked up by key in `oracles`, usually the remaining accounts
+pub struct PriceReader<'a, 'info> {
+    state: &'a State,
+    cache: &'a Cache,
+    zod_state: &'a ZodState,
+    oracles: &'a [AccountInfo<'info>],
+    current_time: u64,
+    current_slot: u64,
+}
+
+impl<'a, 'info> PriceReader<'a, 'info> {
+    pub fn new(
+        state: &'a State,
+        cache: &'a Cache,
+        zod_state: &'a ZodState,
+        oracles: &'a [AccountInfo<'info>],
+        current_time: u64,
+    ) -> Result<Self, ProgramError> {
+        Ok(Self {
+            state,
+            cache,
+            zod_state,
+            oracles,
+            current_time,
+            current_slot: Clock::get()?.slot,
+        })
+    }
+
+    pub fn get_price(&self, index: usize) -> Result<OraclePrice, ProgramError> {
+        let config = self.zod_state.oracle_configs[index];
+        let price = self.read_source(index, config.source, &config.account)?;
+        debug_msg!(
+            "Oracle: collateral index: {}, price: {}, confidence: {}",
+            index,
+            price.price,
+            price.confidence
+        );
+        Ok(price)
+    }
+
+    // errors if the price is further than max_oracle_deviation from the collateral's deviation source
+    pub fn check_deviation(&self, index: usize) -> ProgramResult {
+        let config = self.zod_state.oracle_configs[index];
+        let max_deviation = self.zod_state.max_oracle_deviation;
+        if max_deviation == 0 || OracleSource::try_from(config.deviation_source)? == OracleSource::ZoCache {
+            return Ok(());
+        }
+
+        let price = self.read_source(index, config.source, &config.account)?.price;
+        let reference = self
+            .read_source(index, config.deviation_source, &config.deviation_account)?
+            .price;
+        let deviation = price.safe_sub(reference)?.abs().safe_div(reference)?;
+        debug_msg!("Oracle: collateral index: {}, deviation: {}", index, deviation);
+
+        require!(
+            deviation <= get_bps(max_deviation),
+            ZodError::OracleDeviationTooLarge
+        );
+        Ok(())
+    }
+
+    fn read_source(&self, index: usize, source: u8, account: &Pubkey) -> Result<OraclePrice, ProgramError> {
+        let decimals = self.state.collaterals[index].decimals as u32;
+        match OracleSource::try_from(source)? {
+            OracleSource::ZoCache => self.get_cache_price(index),
+            OracleSource::Pyth => {
+                get_pyth_price(self.get_oracle_account(account)?, decimals, self.current_slot)
+            }
+            OracleSource::Switchboard => {
+                get_switchboard_price(self.get_oracle_account(account)?, decimals, self.current_slot)
+            }
+            #[cfg(feature = "test-oracle")]
+            OracleSource::Test => get_test_price(self.get_oracle_account(account)?, decimals),
+        }
+    }
+
+    fn get_cache_price(&self, index: usize) -> Result<OraclePrice, ProgramError> {
+        let oracle = self
+            .cache
+            .get_oracle(&self.state.collaterals[index].oracle_symbol)?;
+        require!(
+            self.current_time.saturating_sub({ oracle.last_updated }) <= MAX_CACHE_AGE,
+            ZodError::StaleOracle
+        );
+        let price: I80F48 = oracle.price.into();
+        require!(price > I80F48::ZERO, ZodError::InvalidOracle);
+        Ok(OraclePrice {
+            price,
+            confidence: I80F48::ZERO,
+        })
+    }
+
+    fn get_oracle_account(&self, key: &Pubkey) -> Result<&'a AccountInfo<'info>, ProgramError> {
+        self.oracles
+            .iter()
+            .find(|acc| acc.key == key)
+            .ok_or_else(|| ZodError::MissingOracleAccount.into())
+    }
+}
+
+// big usd per big collateral, scaled by a base 10 exponent, to smol usd per smol collateral
+fn to_smol_price(value: I80F48, expo: i32, decimals: u32) -> Result<I80F48, ProgramError> {
+    let scale = 10u64
+        .checked_pow(expo.unsigned_abs())
+        .map(I80F48::from_num)
+        .ok_or(ZodError::MathFailure)?;
+    let big = if expo >= 0 {
+        value.safe_mul(scale)?
+    } else {
+        value.safe_div(scale)?
+    };
+    Ok(big_price_to_smol_price(big, USD_DECIMALS, decimals)?)
+}
+
+pub fn get_pyth_price(
+    account: &AccountInfo,
+    decimals: u32,
+    current_slot: u64,
+) -> Result<OraclePrice, ProgramError> {
+    let data = account.try_borrow_data()?;
+    let price_account = pyth_client::load_price(&data).map_err(|_| ZodError::InvalidOracle)?;
+
+    require!(
+        price_account.agg.status == PriceStatus::Trading
+            && current_slot.saturating_sub(price_account.agg.pub_slot) <= MAX_ORACLE_SLOT_AGE,
+        ZodError::StaleOracle
+    );
+    require!(price_account.agg.price > 0, ZodError::InvalidOracle);
+
+    Ok(OraclePrice {
+        price: to_smol_price(
+            I80F48::from_num(price_account.agg.price),
+            price_account.expo,
+            decimals,
+        )?,
+        confidence: to_smol_price(
+            I80F48::from_num(price_account.agg.conf),
+            price_account.expo,
+            decimals,
+        )?,
+    })
+}
+
+pub fn get_switchboard_price(
+    account: &AccountInfo,
+    decimals: u32,
+    current_slot: u64,
+) -> Result<OraclePrice, ProgramError> {
+    let aggregator = get_aggregator(account).map_err(|_| ZodError::InvalidOracle)?;
+    let round = get_aggregator_result(&aggregator).map_err(|_| ZodError::InvalidOracle)?;
+
+    let open_slot = round.round_open_slot.ok_or(ZodError::InvalidOracle)?;
+    require!(
+        current_slot.saturating_sub(open_slot) <= MAX_ORACLE_SLOT_AGE,
+        ZodError::StaleOracle
+    );
+
+    let result = round.result.ok_or(ZodError::InvalidOracle)?;
+    require!(result > 0.0, ZodError::InvalidOracle);
+
+    // switchboard has no confidence interval, half the spread of the round's responses stands in for it
+    let spread = match (round.min_response, round.max_response) {
+        (Some(min), Some(max)) => (max - min).max(0.0) / 2.0,
+        _ => 0.0,
+    };
+
+    Ok(OraclePrice {
+        price: to_smol_price(I80F48::from_num(result), 0, decimals)?,
+        confidence: to_smol_price(I80F48::from_num(spread), 0, decimals)?,
+    })
+}
+
+#[cfg(feature = "test-oracle")]
+pub fn get_test_price(account: &AccountInfo, decimals: u32) -> Result<OraclePrice, ProgramError> {
+    let oracle = Account::<TestOracle>::try_from(account).map_err(|_| ZodError::InvalidOracle)?;
+    require!(oracle.price > 0, ZodError::InvalidOracle);
+
+    Ok(OraclePrice {
+        price: to_smol_price(I80F48::from_num(oracle.price), oracle.expo, decimals)?,
+        confidence: to_smol_price(I80F48::from_num(oracle.conf), oracle.expo, decimals)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_confidence() {
+        let price = OraclePrice {
+            price: I80F48::from_num(100),
+            confidence: I80F48::from_num(25),
+        };
+        // 2500 bps is exactly the interval, 1250 bps is too tight, zero disables the guard
+        assert!(price.check_confidence(2_500).is_ok());
+        assert!(price.check_confidence(1_250).is_err());
+        assert!(price.check_confidence(0).is_ok());
+        assert_eq!(price.get_lower_bound().unwrap(), 75);
+    }
+
+    #[test]
+    fn test_to_smol_price_negative_expo() {
+        // pyth style 50_000.00000000 usd per btc, btc has 8 decimals
+        let price = to_smol_price(I80F48::from_num(5_000_000_000_000i64), -8, 8).unwrap();
+        assert_eq!(price, 500);
+
+        // 1.00 usd per usdc, usdc has 6 decimals
+        let price = to_smol_price(I80F48::from_num(100), -2, 6).unwrap();
+        assert_eq!(price, 1);
+    }
+
+    #[test]
+    fn test_to_smol_price_positive_expo() {
+        // 5 * 10^4 usd per btc
+        let price = to_smol_price(I80F48::from_num(5), 4, 8).unwrap();
+        assert_eq!(price, 500);
+
+        // 500 usd per sol, sol has 9 decimals
+        let price = to_smol_price(I80F48::from_num(5), 2, 9).unwrap();
+        assert_eq!(price, I80F48::from_num(0.5));
+    }
+
+    #[test]
+    fn test_to_smol_price_decimals() {
+        // 250 usd per token with 9, 6 and 0 decimals
+        let value = I80F48::from_num(25_000);
+        assert_eq!(to_smol_price(value, -2, 9).unwrap(), I80F48::from_num(0.25));
+        assert_eq!(to_smol_price(value, -2, 6).unwrap(), 250);
+        assert_eq!(to_smol_price(value, -2, 0).unwrap(), 250_000_000);
+    }
+
+    #[test]
+    fn test_to_smol_price_rejects_out_of_range_expo() {
+        assert!(to_smol_price(I80F48::ONE, 20, 6).is_err());
+        assert!(to_smol_price(I80F48::ONE, -20, 6).is_err());
+        assert!(to_smol_price(I80F48::ONE, i32::MIN, 6).is_err());
+        assert!(to_smol_price(I80F48::from_num(1_000_000), 19, 6).is_err());
+    }
+}
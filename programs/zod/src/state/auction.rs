@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+// seconds for an auction's discount to grow from zero to the full liquidation fee
+pub const LIQUIDATION_AUCTION_DURATION: u64 = 600;
+
+#[account(zero_copy)]
+pub struct ZodLiquidationAuction {
+    pub nonce: u8,
+    pub margin: Pubkey,
+    pub keeper: Pubkey, // started the auction and receives the rent back
+    pub start_time: u64,
+    pub zod_repaid: u64, // in smol zod
+    pub is_restored: u8, // set when a bid left the margin at or above imf
+}
+
+impl ZodLiquidationAuction {
+    // discount on collateral, ramping linearly from 0 to max_discount over the auction duration.
+    // the ramp restarts once a restored margin falls below mmf again, a margin that recovers on
+    // its own should be closed with end_liquidation_auction
+    pub fn get_discount(&self, max_discount: f64, current_time: u64) -> f64 {
+        let elapsed = current_time
+            .saturating_sub(self.start_time)
+            .min(LIQUIDATION_AUCTION_DURATION);
+        max_discount * elapsed as f64 / LIQUIDATION_AUCTION_DURATION as f64
+    }
+
+    // an open auction takes bids until the margin is back above imf. once a bid restored it, the
+    // margin has to fall below mmf again, the same bar start_liquidation_auction sets
+    pub fn is_biddable(&self, omf: I80F48, imf: i64, mmf: i64) -> bool {
+        match self.is_restored != 0 {
+            true => omf < mmf,
+            false => omf < imf,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::__private::bytemuck::Zeroable;
+
+    #[test]
+    fn test_is_biddable() {
+        let imf = 1_100;
+        let mmf = 1_030;
+        let auction = ZodLiquidationAuction::zeroed();
+
+        assert!(auction.is_biddable(I80F48::from_num(1_050), imf, mmf));
+        assert!(auction.is_biddable(I80F48::from_num(1_000), imf, mmf));
+        assert!(!auction.is_biddable(I80F48::from_num(imf), imf, mmf));
+    }
+
+    #[test]
+    fn test_restored_auction_needs_margin_below_mmf() {
+        let imf = 1_100;
+        let mmf = 1_030;
+        let mut auction = ZodLiquidationAuction::zeroed();
+        auction.is_restored = 1;
+
+        // dipped back between mmf and imf after a restoring bid, above maintenance so not biddable
+        assert!(!auction.is_biddable(I80F48::from_num(1_050), imf, mmf));
+        assert!(!auction.is_biddable(I80F48::from_num(mmf), imf, mmf));
+        assert!(auction.is_biddable(I80F48::from_num(1_000), imf, mmf));
+    }
+
+    #[test]
+    fn test_get_discount() {
+        let mut auction = ZodLiquidationAuction::zeroed();
+        auction.start_time = 1_000;
+
+        assert_eq!(auction.get_discount(0.5, 1_000), 0.0);
+        assert_eq!(auction.get_discount(0.5, 1_000 + LIQUIDATION_AUCTION_DURATION / 2), 0.25);
+        assert_eq!(auction.get_discount(0.5, 1_000 + LIQUIDATION_AUCTION_DURATION * 2), 0.5);
+    }
+}
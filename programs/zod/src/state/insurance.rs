@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(Default)]
+pub struct ZodInsuranceWithdrawal {
+    pub authority: Pubkey,
+    pub shares: u64,       // shares held in escrow, burned once the cooldown has elapsed
+    pub requested_at: u64, // in seconds
+    pub owed: [u64; 25],   // in smol of each collateral, taken out of the fund and waiting for claim_insurance
+}
@@ -1,12 +1,14 @@
+use crate::errors::ZodError;
+use crate::log::debug_msg;
+use crate::oracle::PriceReader;
 use crate::state::*;
 use crate::zodTypes::WrappedI80F48;
 use anchor_lang::prelude::*;
-use common::math::{safe_add_i80f48, safe_div_i80f48, safe_mul_i80f48};
+use anchor_spl::associated_token::get_associated_token_address;
 use common::SafeOp;
 use fixed::types::I80F48;
 use std::cell::Ref;
 use zo::config::DUST_THRESHOLD;
-use zo::errors::ErrorCode;
 use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *};
 
 #[account(zero_copy)]
@@ -27,37 +29,38 @@ enum MfReturnOption {
 }
 
 impl ZodMargin {
-    pub fn bankrupt(&mut self) -> Result<(), ErrorCode> {
+    pub fn bankrupt(&mut self) -> Result<(), ProgramError> {
         self.zod_balance = WrappedI80F48::zero();
         Ok(())
     }
 
+    // returns the change in stored (positive) collateral, for keeping ZodState::total_collateral in sync
     pub fn mutate(
         &mut self,
         index: usize,
         amount: I80F48,
         supply_multiplier: I80F48,
         borrow_multiplier: I80F48,
-    ) -> Result<(), ErrorCode> {
-        msg!("Margin Instruction: mutating collateral");
+    ) -> Result<I80F48, ProgramError> {
+        debug_msg!("Margin Instruction: mutating collateral");
 
         let initial_col: I80F48 = self.collateral[index].into();
         let actual_col = if initial_col > 0 {
-            safe_mul_i80f48(initial_col, supply_multiplier)
+            initial_col.safe_mul(supply_multiplier)?
         } else {
-            safe_mul_i80f48(initial_col, borrow_multiplier)
+            initial_col.safe_mul(borrow_multiplier)?
         };
-        msg!("Margin Instruction: amount of collateral before: {}", actual_col);
-        let final_col = safe_add_i80f48(actual_col, amount);
-        msg!("Margin Instruction: amount of collateral after: {}", final_col);
+        debug_msg!("Margin Instruction: amount of collateral before: {}", actual_col);
+        let final_col = actual_col.safe_add(amount)?;
+        debug_msg!("Margin Instruction: amount of collateral after: {}", final_col);
         let adjusted_final_col = if final_col > 0 {
-            safe_div_i80f48(final_col, supply_multiplier)
+            final_col.safe_div(supply_multiplier)?
         } else {
-            safe_div_i80f48(final_col, borrow_multiplier)
+            final_col.safe_div(borrow_multiplier)?
         };
         self.collateral[index] = WrappedI80F48::from(adjusted_final_col);
 
-        Ok(())
+        Ok(adjusted_final_col.max(I80F48::ZERO).safe_sub(initial_col.max(I80F48::ZERO))?)
     }
 
     pub fn get_omf(
@@ -65,60 +68,59 @@ impl ZodMargin {
         state: &Ref<State>,
         cache: &Ref<Cache>,
         zod_state: &Ref<ZodState>,
+        oracles: &[AccountInfo],
+        history: Option<&ZodOracleHistory>,
         is_weighted: bool,
         current_time: u64,
-        _mock_col_price: Option<u64>,
-    ) -> Result<I80F48, ErrorCode> {
-        msg!("Margin Instruction: getting omf");
+    ) -> Result<I80F48, ProgramError> {
+        debug_msg!("Margin Instruction: getting omf");
         let total_collateral_value = self.get_total_collateral_value(
             state,
             cache,
+            zod_state,
+            oracles,
+            history,
             is_weighted,
             current_time,
-            _mock_col_price,
         )?;
 
         let zod_balance: I80F48 =
-            self.get_actual_zod_balance(zod_state.soc_loss_multiplier.into())?;
+            self.get_actual_zod_balance(zod_state.get_borrow_multiplier()?)?;
 
         let omf = total_collateral_value
             .safe_sub(zod_balance)?
             .safe_mul(1000)?;
-        msg!("Margin Instruction: omf: {}", omf);
+        debug_msg!("Margin Instruction: omf: {}", omf);
         Ok(omf)
     }
 
-    pub fn get_imf(&self, zod_state: &Ref<ZodState>) -> Result<i64, ErrorCode> {
-        msg!("Margin Instruction: getting imf");
-        let zod_base_imf = (SPOT_INITIAL_MARGIN_REQ as u32 / zod_state.zod_token_info.weight as u32)
-            as u16
-            - 1000u16;
-        msg!("Margin Instruction: zod_base_imf: {}", zod_base_imf);
+    pub fn get_imf(&self, zod_state: &Ref<ZodState>) -> Result<i64, ProgramError> {
+        debug_msg!("Margin Instruction: getting imf");
+        let zod_base_imf = get_zod_base_fraction(SPOT_INITIAL_MARGIN_REQ as i64, zod_state)?;
+        debug_msg!("Margin Instruction: zod_base_imf: {}", zod_base_imf);
 
         let zod_balance: I80F48 =
-            self.get_actual_zod_balance(zod_state.soc_loss_multiplier.into())?;
+            self.get_actual_zod_balance(zod_state.get_borrow_multiplier()?)?;
 
-        let imf = (zod_base_imf as i64).safe_mul(zod_balance)?;
-        msg!("Margin Instruction: imf: {}", imf);
+        let imf = zod_base_imf.safe_mul(zod_balance)?;
+        debug_msg!("Margin Instruction: imf: {}", imf);
         Ok(imf)
     }
 
-    pub fn get_mmf(&self, zod_state: &Ref<ZodState>) -> Result<i64, ErrorCode> {
-        msg!("Margin Instruction: getting mmf");
-        let zod_base_mmf = (SPOT_MAINT_MARGIN_REQ as u32 / zod_state.zod_token_info.weight as u32)
-            as u16
-            - 1000u16;
-        msg!("Margin Instruction: zod_base_mmf: {}", zod_base_mmf);
+    pub fn get_mmf(&self, zod_state: &Ref<ZodState>) -> Result<i64, ProgramError> {
+        debug_msg!("Margin Instruction: getting mmf");
+        let zod_base_mmf = get_zod_base_fraction(SPOT_MAINT_MARGIN_REQ as i64, zod_state)?;
+        debug_msg!("Margin Instruction: zod_base_mmf: {}", zod_base_mmf);
 
         let zod_balance: I80F48 =
-            self.get_actual_zod_balance(zod_state.soc_loss_multiplier.into())?;
+            self.get_actual_zod_balance(zod_state.get_borrow_multiplier()?)?;
 
-        let mmf = (zod_base_mmf as i64).safe_mul(zod_balance)?;
-        msg!("Margin Instruction: mmf: {}", mmf);
+        let mmf = zod_base_mmf.safe_mul(zod_balance)?;
+        debug_msg!("Margin Instruction: mmf: {}", mmf);
         Ok(mmf)
     }
 
-    pub fn get_max_reducible(&self, zod_state: &Ref<ZodState>, num_lf: f64, imf: i64, omf: I80F48) -> Result<i64, ErrorCode> {
+    pub fn get_max_reducible(&self, zod_state: &Ref<ZodState>, num_lf: f64, imf: i64, omf: I80F48) -> Result<i64, ProgramError> {
         //calculating max reducible, the amount liqor can buy in terms of zod to get liqee to imf (everything multpllied by total open position)
         //OMF increase = assets transfered * asset price - ( assets transfered * asset price / quote price * (1+liqfee) ) * quote price
         //             = assets transfered * (-fee)
@@ -130,19 +132,20 @@ impl ZodMargin {
         //thus,
         //assets transfered = (IMF - OMF) / (asset base IMF - fee)
 
-        let zod_base_imf = (SPOT_INITIAL_MARGIN_REQ as u32
-            / zod_state.zod_token_info.weight as u32) as u16
-            - 1000u16;
+        let zod_base_imf = get_zod_base_fraction(SPOT_INITIAL_MARGIN_REQ as i64, zod_state)?;
 
-        msg!("Margin Instruction: num_lf {}", num_lf);
-        assert!(I80F48::from_num(zod_base_imf) > I80F48::from_num(num_lf));
+        debug_msg!("Margin Instruction: num_lf {}", num_lf);
+        require!(
+            I80F48::from_num(zod_base_imf) > I80F48::from_num(num_lf),
+            ZodError::LiquidationFeeTooHigh
+        );
         let numerator = imf.safe_sub(omf)?;
-        msg!("Margin Instruction: numerator {}", numerator);
+        debug_msg!("Margin Instruction: numerator {}", numerator);
         let denominator = (I80F48::from_num(zod_base_imf) - I80F48::from_num(num_lf)).to_num::<i64>();
-        msg!("Margin Instruction: denomintor {}", denominator);
+        debug_msg!("Margin Instruction: denomintor {}", denominator);
         let max_assets_transfer = numerator.safe_div(denominator)?;
 
-        msg!("Margin Instruction: max_assets_transfer: {:?}", max_assets_transfer);
+        debug_msg!("Margin Instruction: max_assets_transfer: {:?}", max_assets_transfer);
 
         Ok(max_assets_transfer)
 
@@ -152,16 +155,16 @@ impl ZodMargin {
         &self,
         index: usize,
         supply_multiplier: I80F48,
-    ) -> Result<I80F48, ErrorCode> {
-        msg!("Margin Instruction: getting collateral amount");
+    ) -> Result<I80F48, ProgramError> {
+        debug_msg!("Margin Instruction: getting collateral amount");
         let initial_col: I80F48 = self.collateral[index].into();
-        msg!(
+        debug_msg!(
             "Margin Instruction: initial_col: {}, supply_mutiplier: {}",
             initial_col,
             supply_multiplier
         );
-        let actual_col = safe_mul_i80f48(initial_col, supply_multiplier);
-        msg!("Margin Instruction: actual collateral: {}", actual_col);
+        let actual_col = initial_col.safe_mul(supply_multiplier)?;
+        debug_msg!("Margin Instruction: actual collateral: {}", actual_col);
         Ok(actual_col)
     }
 
@@ -169,12 +172,15 @@ impl ZodMargin {
         &self,
         state: &Ref<State>,
         cache: &Ref<Cache>,
+        zod_state: &ZodState,
+        oracles: &[AccountInfo],
+        history: Option<&ZodOracleHistory>,
         is_weighted: bool,
         current_time: u64,
-        _mock_col_price: Option<u64>,
-    ) -> Result<I80F48, ErrorCode> {
-        msg!("Margin Instruction: getting total collateral value");
+    ) -> Result<I80F48, ProgramError> {
+        debug_msg!("Margin Instruction: getting total collateral value");
 
+        let prices = PriceReader::new(state, cache, zod_state, oracles, current_time)?;
         let mut sum = I80F48::ZERO; // in smol usd
 
         let max_col = state.total_collaterals as usize;
@@ -186,24 +192,27 @@ impl ZodMargin {
             //msg!("collateral_index {:?}", i);
 
             let info = &state.collaterals[i];
-            let borrow = &cache.borrow_cache[i];
 
             if WrappedI80F48::zero() == *v || info.is_empty() {
                 continue;
             }
 
-            let v: I80F48 = self.get_actual_collateral(i, borrow.supply_multiplier.into())?;
-
-            let oracle = cache.get_oracle(&info.oracle_symbol)?;
-            let mut price: I80F48 = oracle.price.into();
-            let stale = oracle.is_stale(current_time);
-            assert!(!stale);
-
-            #[cfg(feature = "devnet")]
-            if let Some(_mock_col_price) = _mock_col_price {
-                msg!("Margin Instruction: price before: {}", price);
-                price = I80F48::from_num(_mock_col_price as f64 / 1000.0);
-                msg!("Margin Instruction: mocking price: {}", price);
+            let v: I80F48 = self.get_actual_collateral(i, zod_state.get_supply_multiplier(i))?;
+
+            // mint and withdraw pass a history and count collateral at the bottom of the oracle's
+            // confidence interval, capped at its moving average, so a spike can't be minted against.
+            // an interval wider than max_oracle_confidence or an average the crank hasn't kept up to
+            // date is refused rather than ignored. liquidations, bankruptcies and redemptions pass
+            // none and use the spot price, a wide interval shouldn't stop them when they're needed
+            let oracle_price = prices.get_price(i)?;
+            let mut price: I80F48 = oracle_price.price;
+            if let (Some(history), true) = (history, is_weighted && v >= 0) {
+                oracle_price.check_confidence(zod_state.max_oracle_confidence)?;
+                require!(!history.is_stale(i, current_time), ZodError::OracleHistoryStale);
+                price = oracle_price.get_lower_bound()?;
+                if let Some(ema) = history.get_ema(i) {
+                    price = price.min(ema);
+                }
             }
 
             //msg!("weight {:?}", info.weight);
@@ -215,7 +224,7 @@ impl ZodMargin {
 
             let value = weighted_price.safe_mul(v)?;
 
-            msg!(
+            debug_msg!(
                 "Margin Instruction: collateral index: {}, collateral amount: {}, collateral value: {}",
                 i,
                 v,
@@ -228,75 +237,129 @@ impl ZodMargin {
         Ok(sum)
     }
 
+    // unweighted collateral value per unit of zod owed, used to rank margins for redemption
+    pub fn get_collateral_ratio(
+        &self,
+        state: &Ref<State>,
+        cache: &Ref<Cache>,
+        zod_state: &Ref<ZodState>,
+        oracles: &[AccountInfo],
+        current_time: u64,
+    ) -> Result<I80F48, ProgramError> {
+        let zod_balance = self.get_actual_zod_balance(zod_state.get_borrow_multiplier()?)?;
+        if zod_balance <= 0 {
+            return Ok(I80F48::MAX);
+        }
+        let total_collateral_value = self.get_total_collateral_value(
+            state,
+            cache,
+            zod_state,
+            oracles,
+            None,
+            false,
+            current_time,
+        )?;
+        Ok(total_collateral_value.safe_div(zod_balance)?)
+    }
+
     pub fn get_total_collateral_value_i64(
         &self,
         state: &Ref<State>,
         cache: &Ref<Cache>,
+        zod_state: &ZodState,
+        oracles: &[AccountInfo],
+        history: Option<&ZodOracleHistory>,
         is_weighted: bool,
         current_time: u64,
-        _mock_col_price: Option<u64>,
-    ) -> Result<i64, ErrorCode> {
-        self.get_total_collateral_value(state, cache, is_weighted, current_time, _mock_col_price)
-            .map(|x| x.floor().checked_to_num().unwrap())
+    ) -> Result<i64, ProgramError> {
+        self.get_total_collateral_value(
+            state,
+            cache,
+            zod_state,
+            oracles,
+            history,
+            is_weighted,
+            current_time,
+        )
+            .and_then(|x| {
+                x.floor()
+                    .checked_to_num()
+                    .ok_or_else(|| ZodError::MathFailure.into())
+            })
+    }
+
+    // refuses to go on when any held collateral's price disagrees with its deviation source
+    pub fn check_oracle_deviation(
+        &self,
+        state: &Ref<State>,
+        cache: &Ref<Cache>,
+        zod_state: &ZodState,
+        oracles: &[AccountInfo],
+        current_time: u64,
+    ) -> ProgramResult {
+        let prices = PriceReader::new(state, cache, zod_state, oracles, current_time)?;
+        for (i, v) in { self.collateral }.iter().enumerate() {
+            if !(i < state.total_collaterals as usize) {
+                break;
+            }
+            if WrappedI80F48::zero() == *v || state.collaterals[i].is_empty() {
+                continue;
+            }
+            prices.check_deviation(i)?;
+        }
+        Ok(())
     }
 
     pub fn zod_mutate(
         &mut self,
         amount: I80F48,
         borrow_multiplier: I80F48,
-    ) -> Result<(), ErrorCode> {
-        msg!("Margin Instruction: mutating zod balance");
-        let actual_amount_borrowed = safe_mul_i80f48(self.zod_balance.into(), borrow_multiplier);
-        msg!("Margin Instruction: zod balance before {}", actual_amount_borrowed);
-        let final_amount_borrowed = safe_add_i80f48(actual_amount_borrowed, amount);
-        msg!("Margin Instruction: zod balance after {}", final_amount_borrowed);
+    ) -> Result<(), ProgramError> {
+        debug_msg!("Margin Instruction: mutating zod balance");
+        let initial_amount_borrowed: I80F48 = self.zod_balance.into();
+        let actual_amount_borrowed = initial_amount_borrowed.safe_mul(borrow_multiplier)?;
+        debug_msg!("Margin Instruction: zod balance before {}", actual_amount_borrowed);
+        let final_amount_borrowed = actual_amount_borrowed.safe_add(amount)?;
+        debug_msg!("Margin Instruction: zod balance after {}", final_amount_borrowed);
         let adjusted_final_amount_borrowed =
-            safe_div_i80f48(final_amount_borrowed, borrow_multiplier);
+            final_amount_borrowed.safe_div(borrow_multiplier)?;
         self.zod_balance = WrappedI80F48::from(adjusted_final_amount_borrowed);
         Ok(())
     }
 
     pub fn has_no_col_above_dust(
         &self,
-        col_infos: &[CollateralInfo; 25],
-        max_col: usize,
+        state: &State,
         cache: &Ref<Cache>,
+        zod_state: &ZodState,
+        oracles: &[AccountInfo],
         current_time: u64,
-        _mock_col_price: Option<u64>,
-    ) -> Result<bool, ErrorCode> {
-        msg!("Margin Instruction: checking if there is collateral above dust");
+    ) -> Result<bool, ProgramError> {
+        debug_msg!("Margin Instruction: checking if there is collateral above dust");
+        let prices = PriceReader::new(state, cache, zod_state, oracles, current_time)?;
+        let max_col = state.total_collaterals as usize;
         let mut has_no_col_above_dust = true;
 
         for (i, v) in { self.collateral }.iter().enumerate() {
-            msg!("Margin Instruction: collateral index: {}", i);
+            debug_msg!("Margin Instruction: collateral index: {}", i);
             if !(i < max_col) {
                 break;
             }
 
-            let info = &col_infos[i];
-            let borrow = &cache.borrow_cache[i];
+            let info = &state.collaterals[i];
 
             if WrappedI80F48::zero() == *v || info.is_empty() {
                 continue;
             }
 
-            let v: I80F48 = self.get_actual_collateral(i, borrow.supply_multiplier.into())?;
+            let v: I80F48 = self.get_actual_collateral(i, zod_state.get_supply_multiplier(i))?;
 
             // todo: should this be weighted or no? currently not weighted
-            let oracle = cache.get_oracle(&info.oracle_symbol)?;
-            let mut price: I80F48 = oracle.price.into();
-            require!(!oracle.is_stale(current_time), OracleCacheStale);
-
-            #[cfg(feature = "devnet")]
-            if let Some(_mock_col_price) = _mock_col_price {
-                msg!("Margin Instruction: price before: {}", price);
-                price = I80F48::from_num(_mock_col_price as f64 / 1000.0);
-                msg!("Margin Instruction: mocking price: {}", price);
-            }
+            let price: I80F48 = prices.get_price(i)?.price;
 
             let value = price.safe_mul(v)?.floor().to_num::<i64>();
 
-            msg!(
+            debug_msg!(
                 "Margin Instruction: collateral index: {}, collateral amount: {}, collateral value: {}",
                 i,
                 v,
@@ -312,24 +375,34 @@ impl ZodMargin {
         Ok(has_no_col_above_dust)
     }
 
-    pub fn get_actual_zod_balance(&self, soc_loss_multiplier: I80F48) -> Result<I80F48, ErrorCode> {
-        msg!("Margin Instruction: getting total zod balance");
+    pub fn get_actual_zod_balance(&self, borrow_multiplier: I80F48) -> Result<I80F48, ProgramError> {
+        debug_msg!("Margin Instruction: getting total zod balance");
 
-        msg!(
-            "Margin Instruction: initial_zod_balance: {}, soc_loss_mutiplier: {}",
+        debug_msg!(
+            "Margin Instruction: initial_zod_balance: {}, borrow_mutiplier: {}",
             self.zod_balance,
-            soc_loss_multiplier
+            borrow_multiplier
         );
 
-        let balance = safe_mul_i80f48(
-            self.zod_balance.into(),
-            soc_loss_multiplier,
-        );
+        let zod_balance: I80F48 = self.zod_balance.into();
+        let balance = zod_balance.safe_mul(borrow_multiplier)?;
 
-        msg!("Margin Instruction: zod_balance: {}", balance);
+        debug_msg!("Margin Instruction: zod_balance: {}", balance);
 
         Ok(balance)
     }
+
+    // older margins recorded an ad-hoc token account, newer ones record the authority's ata
+    pub fn is_valid_zod_token_account(&self, token_account: &Pubkey, mint: &Pubkey) -> bool {
+        *token_account == self.zod_token_account
+            || *token_account == get_associated_token_address(&self.authority, mint)
+    }
+}
+
+// REQ / weight - 1000, in i64 so a small weight can't wrap
+fn get_zod_base_fraction(req: i64, zod_state: &ZodState) -> Result<i64, ProgramError> {
+    let weight = zod_state.zod_token_info.weight as i64;
+    Ok(req.safe_div(weight)?.safe_sub(1000i64)?)
 }
 
 #[test]
@@ -1,5 +1,11 @@
+pub mod auction;
+pub mod insurance;
 pub mod margin;
+pub mod oracle_history;
 pub mod state;
 
+pub use auction::*;
+pub use insurance::*;
 pub use margin::*;
+pub use oracle_history::*;
 pub use state::*;
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use common::SafeOp;
+use fixed::types::I80F48;
+
+use crate::zodTypes::WrappedI80F48;
+
+// in seconds, mint and withdraw refuse an average the crank hasn't touched for longer than this
+pub const MAX_ORACLE_HISTORY_AGE: u64 = 300;
+
+// exponential moving average of each collateral's price, kept up to date by update_oracle_history
+#[account(zero_copy)]
+pub struct ZodOracleHistory {
+    pub nonce: u8,
+    pub period: u64,                     // in seconds, time constant of the average's exponential decay
+    pub ema: [WrappedI80F48; 25],        // in smol usd per smol collateral, zero until the first update
+    pub last_updated: [u64; 25],
+}
+
+impl ZodOracleHistory {
+    pub fn update(&mut self, index: usize, spot: I80F48, current_time: u64) -> Result<(), ProgramError> {
+        let ema: I80F48 = self.ema[index].into();
+        let elapsed = current_time.saturating_sub(self.last_updated[index]);
+
+        // alpha = 1 - e^(-elapsed / period), so the old average decays the same however often
+        // the crank runs, and a long gap never hands the average over to a single spot print
+        let new_ema = if ema == I80F48::ZERO || self.period == 0 {
+            spot
+        } else {
+            let decay = (-(elapsed as f64) / self.period as f64).exp();
+            let alpha = I80F48::from_num(1f64 - decay);
+            ema.safe_add(spot.safe_sub(ema)?.safe_mul(alpha)?)?
+        };
+
+        self.ema[index] = WrappedI80F48::from(new_ema);
+        self.last_updated[index] = current_time;
+        Ok(())
+    }
+
+    pub fn get_ema(&self, index: usize) -> Option<I80F48> {
+        let ema: I80F48 = self.ema[index].into();
+        match ema == I80F48::ZERO {
+            true => None,
+            false => Some(ema),
+        }
+    }
+
+    pub fn is_stale(&self, index: usize, current_time: u64) -> bool {
+        current_time.saturating_sub(self.last_updated[index]) > MAX_ORACLE_HISTORY_AGE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::__private::bytemuck::Zeroable;
+
+    fn history(period: u64) -> ZodOracleHistory {
+        let mut history = ZodOracleHistory::zeroed();
+        history.period = period;
+        history
+    }
+
+    fn assert_close(a: I80F48, b: I80F48) {
+        assert!((a - b).abs() < I80F48::from_num(0.000001), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_first_update_takes_spot() {
+        let mut history = history(600);
+        assert_eq!(history.get_ema(0), None);
+
+        history.update(0, I80F48::from_num(100), 1_000).unwrap();
+        assert_eq!(history.get_ema(0), Some(I80F48::from_num(100)));
+        assert_eq!({ history.last_updated }[0], 1_000);
+        assert_eq!(history.get_ema(1), None);
+    }
+
+    #[test]
+    fn test_update_decays_exponentially() {
+        let mut history = history(600);
+        history.update(0, I80F48::from_num(100), 1_000).unwrap();
+
+        // no time passed, the average doesn't move
+        history.update(0, I80F48::from_num(200), 1_000).unwrap();
+        assert_eq!(history.get_ema(0), Some(I80F48::from_num(100)));
+
+        // one period closes 1 - 1/e of the gap
+        history.update(0, I80F48::from_num(200), 1_600).unwrap();
+        let expected = 200f64 - 100f64 * (-1f64).exp();
+        assert_close(history.get_ema(0).unwrap(), I80F48::from_num(expected));
+    }
+
+    #[test]
+    fn test_update_long_gap_does_not_jump_to_spot() {
+        let mut history = history(600);
+        history.update(0, I80F48::from_num(100), 1_000).unwrap();
+
+        history.update(0, I80F48::from_num(1_000), 1_000 + 600 * 3).unwrap();
+        let ema = history.get_ema(0).unwrap();
+        assert!(ema < I80F48::from_num(1_000));
+        let expected = 1_000f64 - 900f64 * (-3f64).exp();
+        assert_close(ema, I80F48::from_num(expected));
+    }
+
+    #[test]
+    fn test_update_split_matches_single_step() {
+        let mut split = history(600);
+        split.update(0, I80F48::from_num(100), 0).unwrap();
+        split.update(0, I80F48::from_num(200), 300).unwrap();
+        split.update(0, I80F48::from_num(200), 600).unwrap();
+
+        let mut single = history(600);
+        single.update(0, I80F48::from_num(100), 0).unwrap();
+        single.update(0, I80F48::from_num(200), 600).unwrap();
+
+        assert_close(split.get_ema(0).unwrap(), single.get_ema(0).unwrap());
+    }
+
+    #[test]
+    fn test_zero_period_follows_spot() {
+        let mut history = history(0);
+        history.update(0, I80F48::from_num(100), 1_000).unwrap();
+        history.update(0, I80F48::from_num(150), 1_001).unwrap();
+        assert_eq!(history.get_ema(0), Some(I80F48::from_num(150)));
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let mut history = history(600);
+        assert!(history.is_stale(0, 1_000));
+
+        history.update(0, I80F48::from_num(100), 1_000).unwrap();
+        assert!(!history.is_stale(0, 1_000));
+        assert!(!history.is_stale(0, 1_000 + MAX_ORACLE_HISTORY_AGE));
+        assert!(history.is_stale(0, 1_000 + MAX_ORACLE_HISTORY_AGE + 1));
+    }
+}
@@ -1,9 +1,15 @@
 use anchor_lang::prelude::*;
+use common::bps::get_bps;
+use common::math::checked_pow_i80f48;
 use common::SafeOp;
 use fixed::types::I80F48;
 use fixed_macro::types::I80F48;
+use std::convert::TryInto;
 use zo::{self, config::DEBUG_LOG, cpi::accounts::*, program::ZoAbi as Zo, *, accounts::*};
-use zo::errors::ErrorCode;
+
+use crate::errors::ZodError;
+use crate::log::debug_msg;
+use crate::oracle::{PriceReader, ZodOracleConfig};
 
 #[account(zero_copy)]
 pub struct ZodState {
@@ -12,16 +18,104 @@ pub struct ZodState {
   pub admin: Pubkey,
   pub zo_program_state: Pubkey,
   pub zo_program_margin: Pubkey,
-  pub insurance: u64,          // in smol usd
-  pub fees_accrued: [u64; 25], // in smol usd
+  pub insurance: [u64; 25],    // in smol of each collateral
+  pub fees_accrued: [WrappedI80F48; 25], // in smol of each collateral, protocol reserve share of supply yield
   pub total_collaterals: u16,
   pub vaults: [Pubkey; 25],
   pub zod_token_mint: Pubkey,
   pub zod_token_info: ZodCollateralInfo,
   pub soc_loss_multiplier: WrappedI80F48,
   pub total_zod_borrowed: WrappedI80F48,
+  pub stability_fee_rate: WrappedI80F48, // per second
+  pub debt_multiplier: WrappedI80F48,
+  pub last_fee_accrual: u64,
+  pub stability_fees_accrued: WrappedI80F48, // in smol zod
+
+  // peg stability module
+  pub psm_mint: Pubkey,
+  pub psm_vault: Pubkey,
+  pub psm_tin: u16,           // in bps
+  pub psm_tout: u16,          // in bps
+  pub psm_debt_ceiling: u64,  // in smol zod
+  pub psm_debt: u64,          // in smol zod
+  pub psm_fees_accrued: u64,  // in smol usdc
+
+  // redemptions
+  pub redemption_base_rate: WrappedI80F48,
+  pub last_redemption_time: u64,
+  pub redemption_fee_floor: u16, // in bps
+
+  // two step admin transfer
+  pub pending_admin: Pubkey,
+  pub pending_admin_eta: u64,
+  pub admin_timelock: u64, // in seconds
+
+  // emergency pause
+  pub guardian: Pubkey,
+  pub pause_flags: u16, // bit set per ZodAction
+
+  // exposure limits
+  pub collateral_caps: [u64; 25], // in smol of each collateral
+  pub total_deposits: [u64; 25],  // in smol of each collateral, principal only
+  pub zod_debt_ceiling: u64,      // in smol zod
+
+  // flash mints
+  pub flash_mint_fee: u16,         // in bps
+  pub flash_mint_outstanding: u64, // in smol zod, only non zero between flash_mint and flash_repay
+  pub flash_mint_fees_accrued: u64, // in smol zod, burned by flash_repay and reissued by collect_stability_fees
+
+  // insurance fund shares
+  pub insurance_share_mint: Pubkey,
+  pub insurance_cooldown: u64,        // in seconds
+  pub insurance_withdraw_window: u64, // in seconds, opens once the cooldown has elapsed
+  pub min_insurance_ratio: u16,       // in bps of outstanding zod
+
+  // zod side supply accounting, lets part of the 01 supply yield be kept back from depositors
+  pub supply_multipliers: [WrappedI80F48; 25],    // what margin collateral grows by
+  pub zo_supply_multipliers: [WrappedI80F48; 25], // 01 supply multipliers at the last accrual
+  pub total_collateral: [WrappedI80F48; 25],      // sum of margin collateral, divided by supply_multipliers
+
+  // zod savings
+  pub savings_mint: Pubkey,
+  pub savings_vault: Pubkey,
+  pub savings_yield_share: u16,              // in bps of supply yield
+  pub savings_yield: [WrappedI80F48; 25],    // in smol of each collateral, waiting for drip_savings
+  pub savings_minted: u64,                   // in smol zod, dripped so far, counts against zod_debt_ceiling
+
+  // protocol reserve
+  pub reserve_ratio: u16, // in bps of supply yield, accrues into fees_accrued
+
+  // price source per collateral, see oracle.rs
+  pub oracle_configs: [ZodOracleConfig; 25],
+  pub max_oracle_confidence: u16, // in bps of price, 0 disables the check
+  pub max_oracle_deviation: u16,  // in bps between a collateral's two sources, 0 disables the check
 }
 
+#[derive(Clone, Copy)]
+#[repr(u16)]
+pub enum ZodAction {
+  Deposit = 1 << 0,
+  Withdraw = 1 << 1,
+  Mint = 1 << 2,
+  Burn = 1 << 3,
+  Liquidate = 1 << 4,
+  SettleBankruptcy = 1 << 5,
+  Insurance = 1 << 6,
+  Psm = 1 << 7,
+  Redeem = 1 << 8,
+  FlashMint = 1 << 9,
+  Savings = 1 << 10,
+}
+
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+// halves the redemption base rate every 12 hours
+pub const REDEMPTION_DECAY_PER_MINUTE: I80F48 = I80F48!(0.999037758833783);
+
+// smol zod and shares added to both sides of the savings share price, so a first depositor can't
+// inflate it with a donation to the vault and round later deposits down to nothing
+pub const SAVINGS_VIRTUAL_BALANCE: u64 = 1_000_000;
+
 //had trouble getting collateral info from zo
 #[zero_copy]
 #[derive(PartialEq, Default)]
@@ -49,18 +143,41 @@ impl ZodCollateralInfo {
 }
 
 impl ZodState {
-  pub fn mutate_insurance(&mut self, amount: i64) -> Result<(), ErrorCode> {
-    msg!("Zod State Instruction: mutating insurance");
-    msg!("Zod State Instruction: insurance amount before: {}", self.insurance);
-    let initial_insurance = self.insurance as i64;
-    require!(initial_insurance >= -amount, InsufficientInsurance);
-    self.insurance = (initial_insurance + amount) as u64;
-    msg!("Zod State Instruction: insurance amount after: {}", self.insurance);
+  pub fn mutate_insurance(&mut self, index: usize, amount: i64) -> Result<(), ProgramError> {
+    debug_msg!("Zod State Instruction: mutating insurance");
+    let initial_insurance = self.insurance[index] as i64;
+    debug_msg!("Zod State Instruction: insurance amount before: {}", initial_insurance);
+    require!(initial_insurance >= -amount, ZodError::InsufficientInsurance);
+    self.insurance[index] = (initial_insurance + amount) as u64;
+    debug_msg!("Zod State Instruction: insurance amount after: {}", self.insurance[index]);
     Ok(())
   }
 
-  pub fn socialize_loss(&mut self, loss_per_zod_borrowed: I80F48) -> Result<(), ErrorCode> {
-    msg!("Zod State Instruction: Socializing loss");
+  // oracle value of the insurance basket in smol usd, each asset haircut by its zo collateral weight
+  pub fn get_insurance_value(
+    &self,
+    state: &State,
+    cache: &Cache,
+    oracles: &[AccountInfo],
+    current_time: u64,
+  ) -> Result<I80F48, ProgramError> {
+    let prices = PriceReader::new(state, cache, self, oracles, current_time)?;
+    let mut sum = I80F48::ZERO;
+    for i in 0..state.total_collaterals as usize {
+      let amount = { self.insurance }[i];
+      if amount == 0 {
+        continue;
+      }
+      let price = prices.get_price(i)?.price;
+      let weight = I80F48::from_num(state.collaterals[i].weight).safe_div(1000i32)?;
+      sum = sum.safe_add(price.safe_mul(weight)?.safe_mul(I80F48::from_num(amount))?)?;
+    }
+    debug_msg!("Zod State Instruction: insurance value: {}", sum);
+    Ok(sum)
+  }
+
+  pub fn socialize_loss(&mut self, loss_per_zod_borrowed: I80F48) -> Result<(), ProgramError> {
+    debug_msg!("Zod State Instruction: Socializing loss");
     let initial_soc_loss_multiplier: I80F48 = self.soc_loss_multiplier.into();
     self.soc_loss_multiplier = WrappedI80F48::from(
       (I80F48::ONE + loss_per_zod_borrowed).safe_mul(initial_soc_loss_multiplier)?,
@@ -68,25 +185,253 @@ impl ZodState {
     Ok(())
   }
 
-  pub fn get_actual_zod_borrowed(&self) -> I80F48 {
+  // multiplier applied to every stored zod balance, combining socialized losses and stability fees
+  pub fn get_borrow_multiplier(&self) -> Result<I80F48, ProgramError> {
+    let soc_loss_multiplier: I80F48 = self.soc_loss_multiplier.into();
+    let debt_multiplier: I80F48 = self.debt_multiplier.into();
+    Ok(soc_loss_multiplier.safe_mul(debt_multiplier)?)
+  }
+
+  pub fn get_actual_zod_borrowed(&self) -> Result<I80F48, ProgramError> {
     let borrow: I80F48 = self.total_zod_borrowed.into();
-    let multiplier: I80F48 = self.soc_loss_multiplier.into();
-    borrow.safe_mul(multiplier).unwrap()
+    Ok(borrow.safe_mul(self.get_borrow_multiplier()?)?)
+  }
+
+  // everything zod_debt_ceiling bounds, margin debt plus zod minted against the psm reserve and
+  // dripped into savings
+  pub fn get_total_zod_debt(&self) -> Result<I80F48, ProgramError> {
+    Ok(self
+      .get_actual_zod_borrowed()?
+      .safe_add(I80F48::from_num(self.psm_debt))?
+      .safe_add(I80F48::from_num(self.savings_minted))?)
   }
 
-  pub fn mutate_zod_borrowed(&mut self, amount: I80F48) -> Result<(), ErrorCode> {
-    msg!("Zod State Instruction: mutating total zod borrowed");
+  pub fn accrue_stability_fee(&mut self, current_time: u64) -> Result<(), ProgramError> {
+    let elapsed = current_time.saturating_sub(self.last_fee_accrual);
+    if elapsed == 0 {
+      return Ok(());
+    }
+    self.last_fee_accrual = current_time;
+
+    let rate: I80F48 = self.stability_fee_rate.into();
+    if rate == I80F48::ZERO {
+      return Ok(());
+    }
+
+    debug_msg!("Zod State Instruction: accruing stability fee");
+    let borrowed_before = self.get_actual_zod_borrowed()?;
+    let growth = checked_pow_i80f48(I80F48::ONE.safe_add(rate)?, elapsed)?;
+    let initial_debt_multiplier: I80F48 = self.debt_multiplier.into();
+    self.debt_multiplier = WrappedI80F48::from(initial_debt_multiplier.safe_mul(growth)?);
+
+    let fee = self.get_actual_zod_borrowed()?.safe_sub(borrowed_before)?;
+    let initial_fees: I80F48 = self.stability_fees_accrued.into();
+    self.stability_fees_accrued = WrappedI80F48::from(initial_fees.safe_add(fee)?);
+    debug_msg!("Zod State Instruction: stability fee accrued: {}", fee);
+    Ok(())
+  }
+
+  pub fn mutate_zod_borrowed(&mut self, amount: I80F48) -> Result<(), ProgramError> {
+    debug_msg!("Zod State Instruction: mutating total zod borrowed");
     let initial_bor: I80F48 = self.total_zod_borrowed.into();
-    let bor_multiplier: I80F48 = self.soc_loss_multiplier.into();
+    let bor_multiplier: I80F48 = self.get_borrow_multiplier()?;
     let actual_bor = initial_bor.safe_mul(bor_multiplier)?;
-    msg!("Zod State Instruction: total zod borrowed before: {}", actual_bor);
+    debug_msg!("Zod State Instruction: total zod borrowed before: {}", actual_bor);
     let final_bor = actual_bor + amount.max(-actual_bor);
-    msg!("Zod State Instruction: total zod borrowed after: {}", final_bor);
+    debug_msg!("Zod State Instruction: total zod borrowed after: {}", final_bor);
     let adjusted_final_bor = final_bor.safe_div(bor_multiplier)?;
     self.total_zod_borrowed = WrappedI80F48::from(adjusted_final_bor);
     Ok(())
   }
 
+  pub fn get_redemption_fee_rate(&self, current_time: u64) -> Result<I80F48, ProgramError> {
+    let minutes = current_time.saturating_sub(self.last_redemption_time) / 60;
+    let base_rate: I80F48 = self.redemption_base_rate.into();
+    let decayed_rate = base_rate.safe_mul(checked_pow_i80f48(REDEMPTION_DECAY_PER_MINUTE, minutes)?)?;
+    Ok(get_bps(self.redemption_fee_floor).safe_add(decayed_rate)?.min(I80F48::ONE))
+  }
+
+  // bumps the base rate by half the fraction of outstanding zod redeemed, returns the fee rate to charge
+  pub fn register_redemption(&mut self, amount: I80F48, current_time: u64) -> Result<I80F48, ProgramError> {
+    debug_msg!("Zod State Instruction: registering redemption");
+    let floor = get_bps(self.redemption_fee_floor);
+    let decayed_rate = self.get_redemption_fee_rate(current_time)?.safe_sub(floor)?;
+    let redeemed_fraction = amount.safe_div(self.get_actual_zod_borrowed()?.safe_mul(2i8)?)?;
+    let base_rate = decayed_rate.safe_add(redeemed_fraction)?.min(I80F48::ONE);
+
+    self.redemption_base_rate = WrappedI80F48::from(base_rate);
+    self.last_redemption_time = current_time;
+    debug_msg!("Zod State Instruction: redemption base rate: {}", base_rate);
+
+    Ok(floor.safe_add(base_rate)?.min(I80F48::ONE))
+  }
+
+  // moves supply_multipliers along with 01's, keeping savings_yield_share of the growth for savings
+  // and reserve_ratio of it for the protocol. depositors get the rest
+  pub fn accrue_supply_yield(&mut self, cache: &Cache) -> Result<(), ProgramError> {
+    for i in 0..self.total_collaterals as usize {
+      let zo_multiplier: I80F48 = cache.borrow_cache[i].supply_multiplier.into();
+      let prev_zo_multiplier: I80F48 = self.zo_supply_multipliers[i].into();
+
+      // first accrual for this collateral, nothing has been stored against zod's multiplier yet
+      if prev_zo_multiplier == I80F48::ZERO {
+        self.supply_multipliers[i] = zo_multiplier.into();
+        self.zo_supply_multipliers[i] = zo_multiplier.into();
+        continue;
+      }
+      if zo_multiplier <= prev_zo_multiplier {
+        continue;
+      }
+
+      let growth = zo_multiplier.safe_div(prev_zo_multiplier)?.safe_sub(I80F48::ONE)?;
+      let multiplier: I80F48 = self.supply_multipliers[i].into();
+      let total_collateral: I80F48 = self.total_collateral[i].into();
+      let actual_collateral = total_collateral.safe_mul(multiplier)?;
+
+      // the cut is only taken from depositors when it is credited to savings and the reserve,
+      // with nothing tracked in total_collateral the whole growth goes to the margins
+      let (savings_kept, reserve_kept) = if total_collateral > I80F48::ZERO {
+        (
+          growth.safe_mul(get_bps(self.savings_yield_share))?,
+          growth.safe_mul(get_bps(self.reserve_ratio))?,
+        )
+      } else {
+        (I80F48::ZERO, I80F48::ZERO)
+      };
+
+      let savings_yield: I80F48 = self.savings_yield[i].into();
+      self.savings_yield[i] = savings_yield
+        .safe_add(actual_collateral.safe_mul(savings_kept)?)?
+        .into();
+      let fees: I80F48 = self.fees_accrued[i].into();
+      self.fees_accrued[i] = fees
+        .safe_add(actual_collateral.safe_mul(reserve_kept)?)?
+        .into();
+      self.supply_multipliers[i] = multiplier
+        .safe_mul(
+          I80F48::ONE
+            .safe_add(growth)?
+            .safe_sub(savings_kept)?
+            .safe_sub(reserve_kept)?,
+        )?
+        .into();
+      self.zo_supply_multipliers[i] = zo_multiplier.into();
+    }
+    Ok(())
+  }
+
+  pub fn get_supply_multiplier(&self, index: usize) -> I80F48 {
+    self.supply_multipliers[index].into()
+  }
+
+  // amount is in the same divided units ZodMargin::mutate stores collateral in
+  pub fn mutate_total_collateral(&mut self, index: usize, amount: I80F48) -> Result<(), ProgramError> {
+    let initial_col: I80F48 = self.total_collateral[index].into();
+    self.total_collateral[index] = initial_col.safe_add(amount)?.max(I80F48::ZERO).into();
+    Ok(())
+  }
+
+  pub fn is_savings_initialized(&self) -> bool {
+    self.savings_vault != Pubkey::default()
+  }
+
+  pub fn mutate_total_deposits(&mut self, index: usize, amount: i64) -> Result<(), ProgramError> {
+    let initial_deposits = self.total_deposits[index];
+    self.total_deposits[index] = if amount >= 0 {
+      initial_deposits.safe_add(amount as u64)?
+    } else {
+      // withdrawals include accrued interest, so they can exceed the tracked principal
+      initial_deposits.saturating_sub(amount.unsigned_abs())
+    };
+    Ok(())
+  }
+
+  pub fn is_paused(&self, action: ZodAction) -> bool {
+    self.pause_flags & action as u16 != 0
+  }
+
+  pub fn is_psm_initialized(&self) -> bool {
+    self.psm_vault != Pubkey::default()
+  }
+
+  pub fn is_insurance_shares_initialized(&self) -> bool {
+    self.insurance_share_mint != Pubkey::default()
+  }
+
+  // shares to mint for adding value (in smol usd) to a fund currently worth insurance_value
+  pub fn get_insurance_shares(
+    value: I80F48,
+    insurance_value: I80F48,
+    share_supply: u64,
+  ) -> Result<u64, ProgramError> {
+    if share_supply == 0 {
+      return Ok(value.floor().to_num::<u64>());
+    }
+    // outstanding shares of a wiped out fund would be diluted to nothing
+    require!(insurance_value > 0, ZodError::InsuranceDepleted);
+    let shares = value
+      .safe_div(insurance_value)?
+      .safe_mul(I80F48::from_num(share_supply))?;
+    Ok(shares.floor().checked_to_num::<u64>().ok_or(ZodError::MathFailure)?)
+  }
+
+  // insurance that has to stay in the fund, withdrawals can't take it below this
+  pub fn get_min_insurance(&self) -> Result<I80F48, ProgramError> {
+    Ok(self.get_actual_zod_borrowed()?.safe_mul(get_bps(self.min_insurance_ratio))?)
+  }
+
+  // value in smol usd paid out for burning shares of a fund currently worth insurance_value
+  pub fn get_insurance_value_for_shares(
+    shares: u64,
+    insurance_value: I80F48,
+    share_supply: u64,
+  ) -> Result<I80F48, ProgramError> {
+    require!(share_supply > 0, ZodError::InsufficientInsuranceShares);
+    Ok(
+      I80F48::from_num(shares)
+        .safe_div(I80F48::from_num(share_supply))?
+        .safe_mul(insurance_value)?,
+    )
+  }
+
+  // sZOD minted for depositing amount of zod into a savings vault holding vault_balance
+  pub fn get_savings_shares(
+    amount: u64,
+    vault_balance: u64,
+    share_supply: u64,
+  ) -> Result<u64, ProgramError> {
+    let shares = (amount as u128)
+      .safe_mul(share_supply.safe_add(SAVINGS_VIRTUAL_BALANCE)?)?
+      .safe_div(vault_balance.safe_add(SAVINGS_VIRTUAL_BALANCE)?)?;
+    Ok(shares.try_into().map_err(|_| ZodError::MathFailure)?)
+  }
+
+  // zod paid out for burning shares of a savings vault holding vault_balance
+  pub fn get_savings_amount(
+    shares: u64,
+    vault_balance: u64,
+    share_supply: u64,
+  ) -> Result<u64, ProgramError> {
+    let amount = (shares as u128)
+      .safe_mul(vault_balance.safe_add(SAVINGS_VIRTUAL_BALANCE)?)?
+      .safe_div(share_supply.safe_add(SAVINGS_VIRTUAL_BALANCE)?)?;
+    Ok(amount.try_into().map_err(|_| ZodError::MathFailure)?)
+  }
+
+  // smol of collateral index paid out for burning shares, the same fraction of every collateral
+  pub fn get_insurance_payout(
+    &self,
+    index: usize,
+    shares: u64,
+    share_supply: u64,
+  ) -> Result<u64, ProgramError> {
+    require!(share_supply > 0, ZodError::InsufficientInsuranceShares);
+    let amount = ({ self.insurance }[index] as u128)
+      .safe_mul(shares)?
+      .safe_div(share_supply)?;
+    Ok(amount.try_into().map_err(|_| ZodError::MathFailure)?)
+  }
+
   pub fn vaults(&self) -> [Pubkey; 25] {
     self.vaults
   }
@@ -95,3 +440,175 @@ impl ZodState {
     self.key();
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use anchor_lang::__private::bytemuck::Zeroable;
+
+  fn assert_close(a: I80F48, b: I80F48) {
+    assert!((a - b).abs() < I80F48::from_num(0.000001), "{} != {}", a, b);
+  }
+
+  fn redemption_state(borrowed: u64) -> ZodState {
+    let mut state = ZodState::zeroed();
+    state.soc_loss_multiplier = I80F48::ONE.into();
+    state.debt_multiplier = I80F48::ONE.into();
+    state.total_zod_borrowed = I80F48::from_num(borrowed).into();
+    state.redemption_fee_floor = 50;
+    state
+  }
+
+  #[test]
+  fn test_redemption_fee_starts_at_floor() {
+    let state = redemption_state(1_000_000);
+    assert_eq!(state.get_redemption_fee_rate(1_000).unwrap(), get_bps(50));
+  }
+
+  #[test]
+  fn test_register_redemption_adds_half_the_redeemed_fraction() {
+    let mut state = redemption_state(1_000_000);
+    let rate = state.register_redemption(I80F48::from_num(100_000), 1_000).unwrap();
+    assert_close(rate, get_bps(50) + I80F48::from_num(0.05));
+    assert_close(state.redemption_base_rate.into(), I80F48::from_num(0.05));
+    assert_eq!({ state.last_redemption_time }, 1_000);
+  }
+
+  #[test]
+  fn test_redemption_base_rate_halves_every_12_hours() {
+    let mut state = redemption_state(1_000_000);
+    state.redemption_base_rate = I80F48::from_num(0.1).into();
+    state.last_redemption_time = 1_000;
+
+    let rate = state.get_redemption_fee_rate(1_000 + 12 * 3600).unwrap();
+    assert_close(rate, get_bps(50) + I80F48::from_num(0.05));
+
+    let rate = state.get_redemption_fee_rate(1_000 + 24 * 3600).unwrap();
+    assert_close(rate, get_bps(50) + I80F48::from_num(0.025));
+  }
+
+  #[test]
+  fn test_redemption_decay_counts_whole_minutes() {
+    let mut state = redemption_state(1_000_000);
+    state.redemption_base_rate = I80F48::from_num(0.1).into();
+    state.last_redemption_time = 1_000;
+    assert_eq!(
+      state.get_redemption_fee_rate(1_059).unwrap(),
+      state.get_redemption_fee_rate(1_000).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_redemption_fee_is_capped_at_one() {
+    let mut state = redemption_state(1_000_000);
+    let rate = state.register_redemption(I80F48::from_num(5_000_000), 1_000).unwrap();
+    assert_eq!(rate, I80F48::ONE);
+    assert_eq!(state.get_redemption_fee_rate(1_000).unwrap(), I80F48::ONE);
+  }
+
+  #[test]
+  fn test_first_insurance_shares_match_value() {
+    let shares = ZodState::get_insurance_shares(I80F48::from_num(1_000.9), I80F48::ZERO, 0).unwrap();
+    assert_eq!(shares, 1_000);
+  }
+
+  #[test]
+  fn test_insurance_shares_round_down() {
+    // fund worth 3, 2 shares out: adding 1 is worth 2/3 of a share
+    let shares =
+      ZodState::get_insurance_shares(I80F48::ONE, I80F48::from_num(3), 2).unwrap();
+    assert_eq!(shares, 0);
+
+    let shares =
+      ZodState::get_insurance_shares(I80F48::from_num(3), I80F48::from_num(3), 2).unwrap();
+    assert_eq!(shares, 2);
+  }
+
+  #[test]
+  fn test_insurance_shares_rejected_for_depleted_fund() {
+    assert!(ZodState::get_insurance_shares(I80F48::ONE, I80F48::ZERO, 100).is_err());
+  }
+
+  #[test]
+  fn test_insurance_value_for_shares_is_pro_rata() {
+    let value =
+      ZodState::get_insurance_value_for_shares(25, I80F48::from_num(1_000), 100).unwrap();
+    assert_eq!(value, 250);
+
+    // losses lower the value of every share alike
+    let value =
+      ZodState::get_insurance_value_for_shares(25, I80F48::from_num(400), 100).unwrap();
+    assert_eq!(value, 100);
+
+    let value = ZodState::get_insurance_value_for_shares(25, I80F48::ZERO, 100).unwrap();
+    assert_eq!(value, 0);
+  }
+
+  #[test]
+  fn test_insurance_value_for_shares_rejected_without_supply() {
+    assert!(ZodState::get_insurance_value_for_shares(1, I80F48::from_num(1_000), 0).is_err());
+  }
+
+  #[test]
+  fn test_insurance_shares_round_trip_does_not_gain_value() {
+    let insurance_value = I80F48::from_num(1_000_003);
+    let supply = 999_999;
+    let shares =
+      ZodState::get_insurance_shares(I80F48::from_num(12_345), insurance_value, supply).unwrap();
+    let value = ZodState::get_insurance_value_for_shares(
+      shares,
+      insurance_value + I80F48::from_num(12_345),
+      supply + shares,
+    )
+    .unwrap();
+    assert!(value <= I80F48::from_num(12_345));
+  }
+
+  #[test]
+  fn test_insurance_payout_is_same_fraction_of_every_collateral() {
+    let mut state = ZodState::zeroed();
+    state.insurance[0] = 1_000_000;
+    state.insurance[1] = 3_000;
+
+    assert_eq!(state.get_insurance_payout(0, 25, 100).unwrap(), 250_000);
+    assert_eq!(state.get_insurance_payout(1, 25, 100).unwrap(), 750);
+    assert_eq!(state.get_insurance_payout(2, 25, 100).unwrap(), 0);
+  }
+
+  #[test]
+  fn test_insurance_payout_rounds_down() {
+    let mut state = ZodState::zeroed();
+    state.insurance[0] = 10;
+
+    assert_eq!(state.get_insurance_payout(0, 1, 3).unwrap(), 3);
+    assert_eq!(state.get_insurance_payout(0, 3, 3).unwrap(), 10);
+    assert!(state.get_insurance_payout(0, 1, 0).is_err());
+  }
+
+  #[test]
+  fn test_first_savings_deposit_gets_shares_one_to_one() {
+    assert_eq!(ZodState::get_savings_shares(5_000_000, 0, 0).unwrap(), 5_000_000);
+    assert_eq!(ZodState::get_savings_amount(5_000_000, 5_000_000, 5_000_000).unwrap(), 5_000_000);
+  }
+
+  #[test]
+  fn test_savings_shares_follow_dripped_yield() {
+    // vault doubled through drips, new zod buys about half as many shares
+    let shares = ZodState::get_savings_shares(1_000_000, 20_000_000, 10_000_000).unwrap();
+    assert_eq!(shares, 523_809);
+    let amount = ZodState::get_savings_amount(shares, 21_000_000, 10_000_000 + shares).unwrap();
+    assert!(amount <= 1_000_000);
+  }
+
+  #[test]
+  fn test_savings_donation_can_not_round_deposits_to_zero() {
+    // attacker holds a single share and donates 1_000 zod straight to the vault
+    let vault_balance = 1 + 1_000_000_000;
+    let shares = ZodState::get_savings_shares(1_000_000, vault_balance, 1).unwrap();
+    assert!(shares > 0);
+
+    // the donation mostly went to the virtual shares, the attacker can't take it back
+    let attacker = ZodState::get_savings_amount(1, vault_balance, 1 + shares).unwrap();
+    assert!(attacker < 1_000_000);
+  }
+}
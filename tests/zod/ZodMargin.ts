@@ -1,6 +1,10 @@
 const anchor = require("@project-serum/anchor");
 import { BN, Program } from "@project-serum/anchor";
-import { TOKEN_PROGRAM_ID, AccountLayout} from "@solana/spl-token";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  Token,
+} from "@solana/spl-token";
 import {
   Commitment,
   Keypair,
@@ -25,6 +29,10 @@ import { ZodMarginSchema } from "./zodTypes";
 
 const ZOD_TOKEN_ACCOUNT_SIZE = 165;
 
+/** Pyth, switchboard or test oracle accounts for collaterals that are not priced from the 01 cache. */
+const oracleMetas = (oracles: PublicKey[]) =>
+  oracles.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }));
+
 interface Schema extends Omit<ZodMarginSchema, "collateral"> {
   /** The deposit amount divided by the entry supply or borrow multiplier */
   rawCollateral: Decimal[];
@@ -94,18 +102,20 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
       zodProgram.programId,
     );
 
-    const zodTokenAcc = await Keypair.generate();
-
-    const zodTokenAccLamports = await conn.getMinimumBalanceForRentExemption(
-      AccountLayout.span,
+    const zodTokenAcc = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      zodState.mint,
+      zodProgram.provider.wallet.publicKey,
     );
 
     console.log({
       zodState: zodState.pubkey.toString(),
       payer: zodProgram.provider.wallet.publicKey.toString(),
       margin: zodMarginKey.toString(),
-      tokenAccount: zodTokenAcc.publicKey.toString(),
+      tokenAccount: zodTokenAcc.toString(),
       tokenProgram: TOKEN_PROGRAM_ID.toString(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID.toString(),
       mint: zodState.mint.toString(),
       authority: zodProgram.provider.wallet.publicKey.toString(),
       rent: SYSVAR_RENT_PUBKEY.toString(),
@@ -117,23 +127,14 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
         zodState: zodState.pubkey,
         payer: zodProgram.provider.wallet.publicKey,
         margin: zodMarginKey,
-        tokenAccount: zodTokenAcc.publicKey,
+        tokenAccount: zodTokenAcc,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         mint: zodState.mint,
         authority: zodProgram.provider.wallet.publicKey,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
       },
-      preInstructions: [
-        SystemProgram.createAccount({
-          fromPubkey: zoProgram.provider.wallet.publicKey,
-          newAccountPubkey: zodTokenAcc.publicKey,
-          lamports: zodTokenAccLamports,
-          space: AccountLayout.span,
-          programId: TOKEN_PROGRAM_ID,
-        }),
-      ],
-      signers: [zodTokenAcc],
     });
 
     console.log("tx:", tx);
@@ -146,7 +147,7 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
       zodState,
       zoState,
       cache,
-      zodTokenAcc.publicKey,
+      zodTokenAcc,
     );
   }
 
@@ -221,6 +222,7 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
     control: PublicKey,
     amount: BN,
   ) {
+    const [oracleHistory] = await this.zodState.getOracleHistoryAddress();
     return await this.zodProgram.rpc.zodWithdraw(amount, {
       accounts: {
         zoProgramMargin: this.zodState.data.zoProgramMargin,
@@ -231,6 +233,7 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
         cache: cache,
         authority: this.wallet.publicKey,
         zodMargin: this.pubkey,
+        oracleHistory: oracleHistory,
         control: control,
         zodAccount: this.zodTokenAccount,
         tokenAccount: tokenAccount,
@@ -241,7 +244,13 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
     });
   }
 
-  async mintRaw(tokenAccount: PublicKey, cache: PublicKey, amount: BN) {
+  async mintRaw(
+    tokenAccount: PublicKey,
+    cache: PublicKey,
+    amount: BN,
+    oracles: PublicKey[] = [],
+  ) {
+    const [oracleHistory] = await this.zodState.getOracleHistoryAddress();
     return await this.zodProgram.rpc.zodMint(amount, {
       accounts: {
         zodState: this.zodState.pubkey,
@@ -249,10 +258,12 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
         cache: cache,
         authority: this.wallet.publicKey,
         zodMargin: this.pubkey,
+        oracleHistory: oracleHistory,
         tokenAccount: tokenAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
         mint: this.zodState.mint,
       },
+      remainingAccounts: oracleMetas(oracles),
     });
   }
 
@@ -272,12 +283,12 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
   async liquidate(
     tokenAccount: PublicKey,
     liqee_margin: PublicKey,
-    quoteMint: PublicKey,
     cache: PublicKey,
-    amount: BN,
-    testPrice?: BN,
+    legs: { collateralIndex: number; amount: BN }[],
+    oracles: PublicKey[] = [],
   ) {
-    return await this.zodProgram.rpc.liquidateZodPosition(amount, testPrice, {
+    const [auction] = await this.getAuctionAddress(liqee_margin);
+    return await this.zodProgram.rpc.liquidateZodPosition(legs, {
       accounts: {
         zodState: this.zodState.pubkey,
         zoProgramState: this.zodState.data.zoProgramState,
@@ -285,22 +296,103 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
         liqor: this.wallet.publicKey,
         liqorZodMargin: this.pubkey,
         liqeeZodMargin: liqee_margin,
+        auction: auction,
         zodMint: this.zodState.mint,
-        quoteMint: quoteMint,
         tokenAccount: tokenAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
       },
+      remainingAccounts: oracleMetas(oracles),
+    });
+  }
+
+  private async getAuctionAddress(liqeeMargin: PublicKey) {
+    return await PublicKey.findProgramAddress(
+      [
+        liqeeMargin.toBuffer(),
+        this.zodState.pubkey.toBuffer(),
+        anchor.utils.bytes.utf8.encode("zodauctionv1"),
+      ],
+      this.zodProgram.programId,
+    );
+  }
+
+  async startLiquidationAuction(
+    liqeeMargin: PublicKey,
+    cache: PublicKey,
+    oracles: PublicKey[] = [],
+  ) {
+    const [auction, nonce] = await this.getAuctionAddress(liqeeMargin);
+    return await this.zodProgram.rpc.startLiquidationAuction(nonce, {
+      accounts: {
+        zodState: this.zodState.pubkey,
+        zoProgramState: this.zodState.data.zoProgramState,
+        cache: cache,
+        keeper: this.wallet.publicKey,
+        liqeeZodMargin: liqeeMargin,
+        auction: auction,
+        systemProgram: SystemProgram.programId,
+      },
+      remainingAccounts: oracleMetas(oracles),
+    });
+  }
+
+  async bidAuction(
+    tokenAccount: PublicKey,
+    liqeeMargin: PublicKey,
+    cache: PublicKey,
+    collateralIndex: number,
+    amount: BN,
+    oracles: PublicKey[] = [],
+  ) {
+    const [auction] = await this.getAuctionAddress(liqeeMargin);
+    return await this.zodProgram.rpc.bidAuction(
+      collateralIndex,
+      amount,
+      {
+        accounts: {
+          zodState: this.zodState.pubkey,
+          zoProgramState: this.zodState.data.zoProgramState,
+          cache: cache,
+          bidder: this.wallet.publicKey,
+          bidderZodMargin: this.pubkey,
+          liqeeZodMargin: liqeeMargin,
+          auction: auction,
+          zodMint: this.zodState.mint,
+          tokenAccount: tokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts: oracleMetas(oracles),
+      },
+    );
+  }
+
+  async endLiquidationAuction(
+    liqeeMargin: PublicKey,
+    keeper: PublicKey,
+    cache: PublicKey,
+    oracles: PublicKey[] = [],
+  ) {
+    const [auction] = await this.getAuctionAddress(liqeeMargin);
+    return await this.zodProgram.rpc.endLiquidationAuction({
+      accounts: {
+        zodState: this.zodState.pubkey,
+        zoProgramState: this.zodState.data.zoProgramState,
+        cache: cache,
+        keeper: keeper,
+        liqeeZodMargin: liqeeMargin,
+        auction: auction,
+      },
+      remainingAccounts: oracleMetas(oracles),
     });
   }
 
   async settleBankruptcy(
     tokenAccount: PublicKey,
     liqee_margin: PublicKey,
-    quoteMint: PublicKey,
     cache: PublicKey,
-    testPrice?: BN,
+    oracles: PublicKey[] = [],
   ) {
-    return await this.zodProgram.rpc.zodSettleBankruptcy(testPrice, {
+    return await this.zodProgram.rpc.zodSettleBankruptcy({
       accounts: {
         zodState: this.zodState.pubkey,
         zoProgramState: this.zodState.data.zoProgramState,
@@ -309,10 +401,29 @@ export default class ZodMargin extends ZodBaseAccount<Schema> {
         liqorZodMargin: this.pubkey,
         liqeeZodMargin: liqee_margin,
         zodMint: this.zodState.mint,
-        quoteMint: quoteMint,
         tokenAccount: tokenAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
       },
+      remainingAccounts: oracleMetas(oracles),
+    });
+  }
+
+  async close(
+    cache: PublicKey,
+    closeTokenAccount: boolean,
+    oracles: PublicKey[] = [],
+  ) {
+    return await this.zodProgram.rpc.closeZodMargin(closeTokenAccount, {
+      accounts: {
+        zodState: this.zodState.pubkey,
+        zoProgramState: this.zodState.data.zoProgramState,
+        cache: cache,
+        authority: this.wallet.publicKey,
+        margin: this.pubkey,
+        tokenAccount: this.zodTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      remainingAccounts: oracleMetas(oracles),
     });
   }
 
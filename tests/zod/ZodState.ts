@@ -1,11 +1,16 @@
 const anchor = require("@project-serum/anchor");
 import { BN, Program } from "@project-serum/anchor";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  Token,
+} from "@solana/spl-token";
 import {
   Commitment,
   Keypair,
   PublicKey,
   SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   SYSVAR_RENT_PUBKEY,
   TransactionInstruction,
 } from "@solana/web3.js";
@@ -43,7 +48,7 @@ export default class ZodState extends ZodBaseAccount<ZodStateSchema> {
     mint: PublicKey,
   ): Promise<ZodState> {
     const [key, _nonce] = await PublicKey.findProgramAddress(
-      [anchor.utils.bytes.utf8.encode("zodv12")],
+      [anchor.utils.bytes.utf8.encode("zodv13")],
       zodProgram.programId);
 
     const data = await this.fetch(zodProgram, key, st, ch);
@@ -67,7 +72,7 @@ export default class ZodState extends ZodBaseAccount<ZodStateSchema> {
     mintLamports] 
     = await Promise.all([
       PublicKey.findProgramAddress(
-        [anchor.utils.bytes.utf8.encode("zodv12")],
+        [anchor.utils.bytes.utf8.encode("zodv13")],
         zodProgram.programId),
         Keypair.generate(),
         Keypair.generate(),
@@ -133,12 +138,22 @@ export default class ZodState extends ZodBaseAccount<ZodStateSchema> {
     return {...data}
   }
 
+  async getInsuranceShareAccount(owner: PublicKey) {
+    return await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      this.data.insuranceShareMint,
+      owner,
+    );
+  }
+
   async addInsurance(
     tokenAccount: PublicKey,
     zodVault: PublicKey,
     zoVault: PublicKey,
     stateSigner: PublicKey,
     cache: PublicKey,
+    shareAccount: PublicKey,
     amount: BN,
   ) {
     return await this.zodProgram.rpc.zodAddInsurance(amount, {
@@ -153,21 +168,176 @@ export default class ZodState extends ZodBaseAccount<ZodStateSchema> {
         tokenAccount: tokenAccount,
         zoVault: zoVault,
         zodVault: zodVault,
+        shareMint: this.data.insuranceShareMint,
+        shareAccount: shareAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
       },
     });
   }
 
-  async reduceInsurance(
+  async getOracleHistoryAddress() {
+    return await PublicKey.findProgramAddress(
+      [this.pubkey.toBuffer(), anchor.utils.bytes.utf8.encode("zodoraclehistoryv1")],
+      this.zodProgram.programId,
+    );
+  }
+
+  async initOracleHistory(period: BN) {
+    const [oracleHistory, nonce] = await this.getOracleHistoryAddress();
+    return await this.zodProgram.rpc.initOracleHistory(nonce, period, {
+      accounts: {
+        zodState: this.pubkey,
+        admin: this.wallet.publicKey,
+        oracleHistory: oracleHistory,
+        systemProgram: SystemProgram.programId,
+      },
+    });
+  }
+
+  async updateOracleHistory() {
+    const [oracleHistory] = await this.getOracleHistoryAddress();
+    return await this.zodProgram.rpc.updateOracleHistory({
+      accounts: {
+        zodState: this.pubkey,
+        zoProgramState: this.data.zoProgramState,
+        cache: this.state.cache.pubkey,
+        oracleHistory: oracleHistory,
+      },
+    });
+  }
+
+  async getTestOracleAddress(testOracleProgram: Program, collateralMint: PublicKey) {
+    return await PublicKey.findProgramAddress(
+      [
+        this.wallet.publicKey.toBuffer(),
+        collateralMint.toBuffer(),
+        anchor.utils.bytes.utf8.encode("zodtestoraclev1"),
+      ],
+      testOracleProgram.programId,
+    );
+  }
+
+  /**
+   * Prices a collateral from the zod-test-oracle program, only read when zod is built with `test-oracle`.
+   * The price is in big usd per big collateral, scaled by 10^expo like pyth.
+   */
+  async setTestOraclePrice(
+    testOracleProgram: Program,
+    collateralIndex: number,
+    collateralMint: PublicKey,
+    price: BN,
+    expo: number,
+    conf: BN = new BN(0),
+  ) {
+    const [testOracle, nonce] = await this.getTestOracleAddress(
+      testOracleProgram,
+      collateralMint,
+    );
+    await testOracleProgram.rpc.setPrice(nonce, price, conf, expo, {
+      accounts: {
+        authority: this.wallet.publicKey,
+        mint: collateralMint,
+        oracle: testOracle,
+        systemProgram: SystemProgram.programId,
+      },
+    });
+    return await this.zodProgram.rpc.setOracleSource(collateralIndex, 3, 0, {
+      accounts: {
+        zodState: this.pubkey,
+        admin: this.wallet.publicKey,
+        zoProgramState: this.data.zoProgramState,
+        cache: this.state.cache.pubkey,
+        oracle: testOracle,
+        deviationOracle: testOracle,
+      },
+    });
+  }
+
+  private async getInsuranceWithdrawalAddress() {
+    return await PublicKey.findProgramAddress(
+      [
+        this.wallet.publicKey.toBuffer(),
+        this.pubkey.toBuffer(),
+        anchor.utils.bytes.utf8.encode("zodinsurancewithdrawv1"),
+      ],
+      this.zodProgram.programId,
+    );
+  }
+
+  private async getInsuranceEscrowAddress(withdrawal: PublicKey) {
+    return await PublicKey.findProgramAddress(
+      [withdrawal.toBuffer(), anchor.utils.bytes.utf8.encode("zodinsuranceescrowv1")],
+      this.zodProgram.programId,
+    );
+  }
+
+  async requestInsuranceWithdrawal(shareAccount: PublicKey, shares: BN) {
+    const [withdrawal, nonce] = await this.getInsuranceWithdrawalAddress();
+    const [escrow] = await this.getInsuranceEscrowAddress(withdrawal);
+    return await this.zodProgram.rpc.requestInsuranceWithdrawal(nonce, shares, {
+      accounts: {
+        zodState: this.pubkey,
+        authority: this.wallet.publicKey,
+        shareMint: this.data.insuranceShareMint,
+        shareAccount: shareAccount,
+        withdrawal: withdrawal,
+        escrow: escrow,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      },
+    });
+  }
+
+  /** Returns escrowed shares that haven't been reduced yet, the rest keep their cooldown. */
+  async cancelInsuranceWithdrawal(shareAccount: PublicKey, shares: BN) {
+    const [withdrawal] = await this.getInsuranceWithdrawalAddress();
+    const [escrow] = await this.getInsuranceEscrowAddress(withdrawal);
+    return await this.zodProgram.rpc.cancelInsuranceWithdrawal(shares, {
+      accounts: {
+        zodState: this.pubkey,
+        authority: this.wallet.publicKey,
+        shareAccount: shareAccount,
+        withdrawal: withdrawal,
+        escrow: escrow,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    });
+  }
+
+  /** Burns escrowed shares for the same fraction of every insurance collateral, claimed with claimInsurance. */
+  async reduceInsurance(cache: PublicKey, shares: BN, oracles: PublicKey[] = []) {
+    const [withdrawal] = await this.getInsuranceWithdrawalAddress();
+    const [escrow] = await this.getInsuranceEscrowAddress(withdrawal);
+    return await this.zodProgram.rpc.zodReduceInsurance(shares, {
+      accounts: {
+        zodState: this.pubkey,
+        zoProgramState: this.data.zoProgramState,
+        cache: cache,
+        authority: this.wallet.publicKey,
+        shareMint: this.data.insuranceShareMint,
+        withdrawal: withdrawal,
+        escrow: escrow,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      remainingAccounts: oracles.map((pubkey) => ({
+        pubkey,
+        isWritable: false,
+        isSigner: false,
+      })),
+    });
+  }
+
+  async claimInsurance(
     tokenAccount: PublicKey,
     zodVault: PublicKey,
     zoVault: PublicKey,
     stateSigner: PublicKey,
     cache: PublicKey,
     control: PublicKey,
-    amount: BN,
   ) {
-    return await this.zodProgram.rpc.zodReduceInsurance(amount, {
+    const [withdrawal] = await this.getInsuranceWithdrawalAddress();
+    return await this.zodProgram.rpc.claimInsurance({
       accounts: {
         zoProgramMargin: this.data.zoProgramMargin,
         zoProgram: this.program.programId,
@@ -175,11 +345,100 @@ export default class ZodState extends ZodBaseAccount<ZodStateSchema> {
         zoProgramState: this.data.zoProgramState,
         stateSigner: stateSigner,
         cache: cache,
-        admin: this.wallet.publicKey,
+        authority: this.wallet.publicKey,
         control: control,
         tokenAccount: tokenAccount,
         zoVault: zoVault,
         zodVault: zodVault,
+        withdrawal: withdrawal,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    });
+  }
+
+  async getSavingsShareAccount(owner: PublicKey) {
+    return await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      this.data.savingsMint,
+      owner,
+    );
+  }
+
+  async savingsDeposit(
+    tokenAccount: PublicKey,
+    shareAccount: PublicKey,
+    amount: BN,
+    oracles: PublicKey[] = [],
+  ) {
+    return await this.zodProgram.rpc.savingsDeposit(amount, {
+      accounts: {
+        zodState: this.pubkey,
+        zoProgramState: this.data.zoProgramState,
+        cache: this.state.cache.pubkey,
+        mint: this.data.zodTokenInfo.mint,
+        authority: this.wallet.publicKey,
+        tokenAccount: tokenAccount,
+        savingsVault: this.data.savingsVault,
+        savingsMint: this.data.savingsMint,
+        shareAccount: shareAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      remainingAccounts: oracles.map((pubkey) => ({
+        pubkey,
+        isWritable: false,
+        isSigner: false,
+      })),
+    });
+  }
+
+  async savingsWithdraw(
+    tokenAccount: PublicKey,
+    shareAccount: PublicKey,
+    shares: BN,
+    oracles: PublicKey[] = [],
+  ) {
+    return await this.zodProgram.rpc.savingsWithdraw(shares, {
+      accounts: {
+        zodState: this.pubkey,
+        zoProgramState: this.data.zoProgramState,
+        cache: this.state.cache.pubkey,
+        mint: this.data.zodTokenInfo.mint,
+        authority: this.wallet.publicKey,
+        tokenAccount: tokenAccount,
+        savingsVault: this.data.savingsVault,
+        savingsMint: this.data.savingsMint,
+        shareAccount: shareAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      remainingAccounts: oracles.map((pubkey) => ({
+        pubkey,
+        isWritable: false,
+        isSigner: false,
+      })),
+    });
+  }
+
+  async dripSavings() {
+    return await this.zodProgram.rpc.dripSavings({
+      accounts: {
+        zodState: this.pubkey,
+        zoProgramState: this.data.zoProgramState,
+        cache: this.state.cache.pubkey,
+        mint: this.data.zodTokenInfo.mint,
+        savingsVault: this.data.savingsVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    });
+  }
+
+  async flashMint(tokenAccount: PublicKey, amount: BN) {
+    return await this.zodProgram.rpc.flashMint(amount, {
+      accounts: {
+        zodState: this.pubkey,
+        mint: this.data.zodTokenInfo.mint,
+        tokenAccount: tokenAccount,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
       },
     });
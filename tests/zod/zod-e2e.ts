@@ -1,4 +1,11 @@
-import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import assert from "assert";
 import * as anchor from "@project-serum/anchor";
 //@ts-ignore
@@ -57,6 +64,7 @@ function initZodState(ts: any) {
       ts.state = await State.load(ts.program, SETTINGS.zoState);
 
       ts.zodState = await ZodState.create(ts.zodProgram, ts.program, ts.state);
+      await ts.zodState.initOracleHistory(new BN(600));
 
       ts.zodStateUSDCVault = await createTokenAccount(
         ts.god,
@@ -205,6 +213,7 @@ function zodAlice(ts: any) {
         ts.alice,
         [ts.aliceUSDC, ts.stateUSDCVault],
         async () => {
+          await ts.zodState.updateOracleHistory();
           const tx = await ts.aliceZodMargin.withdrawRaw(
             ts.aliceUSDC,
             SETTINGS.zodStateUSDCVault,
@@ -231,6 +240,7 @@ function zodAlice(ts: any) {
         ts.alice,
         [ts.aliceZodMargin.zodTokenAccount],
         async () => {
+          await ts.zodState.updateOracleHistory();
           const tx = await ts.aliceZodMargin.mintRaw(
             ts.aliceZodMargin.zodTokenAccount,
             ts.state.cache.pubkey,
@@ -286,6 +296,24 @@ function zodAlice(ts: any) {
       assert.equal(zodTokenChange1, 0);
     });
 
+    it("stops a flash mint without a flash repay", async () => {
+      const flashAmount = new BN(10 * 10 ** USDC_DECIMALS);
+
+      const [zodTokenChange] = await withBalanceChange(
+        ts.alice,
+        [ts.aliceZodMargin.zodTokenAccount],
+        async () => {
+          await assert.rejects(
+            ts.zodState.flashMint(ts.aliceZodMargin.zodTokenAccount, flashAmount),
+            (err: any) =>
+              err.msg ===
+              "Flash mint must be followed by a flash_repay in the same transaction."
+          );
+        }
+      );
+      assert.equal(zodTokenChange, 0);
+    });
+
     it("allows Alice to burn funds", async () => {
       const burnAmount = new BN(9 * 10 ** USDC_DECIMALS);
 
@@ -427,6 +455,9 @@ function zodAlice(ts: any) {
 
     xit("Bob liquidates Alice", async () => {
       const liquidateAmount = new BN(700 * 10 ** USDC_DECIMALS);
+      // prices usdc at 0.25 so Alice's margin is under water
+      await ts.zodState.setTestOraclePrice(ts.testOracleProgram, 0, ts.usdcMint, new BN(25), -2);
+      const [usdcOracle] = await ts.zodState.getTestOracleAddress(ts.testOracleProgram, ts.usdcMint);
 
       const [zodTokenChange] = await withBalanceChange(
         ts.bob,
@@ -435,10 +466,9 @@ function zodAlice(ts: any) {
           const tx = await ts.bobZodMargin.liquidate(
             ts.bobZodMargin.zodTokenAccount,
             ts.aliceZodMargin.pubkey,
-            ts.usdcMint,
             ts.state.cache.pubkey,
-            liquidateAmount,
-            new BN(250)
+            [{ collateralIndex: 0, amount: liquidateAmount }],
+            [usdcOracle]
           );
           await ts.bob.connection.confirmTransaction(tx, "finalized");
           console.log("tx ", tx);
@@ -473,6 +503,7 @@ function bankruptcy(ts: any) {
             ts.stateUSDCVault,
             ts.state.signer,
             ts.state.cache.pubkey,
+            await ts.zodState.getInsuranceShareAccount(ts.god.wallet.publicKey),
             addInsuranceAmount
           );
           await ts.god.connection.confirmTransaction(tx, "finalized");
@@ -484,23 +515,33 @@ function bankruptcy(ts: any) {
     });
 
     xit("can reduce insurance fund", async () => {
+      // shares are minted 1:1 with the first insurance deposit
       const reduceInsuranceAmount = new BN(500 * 10 ** USDC_DECIMALS);
+      const godShares = await ts.zodState.getInsuranceShareAccount(
+        ts.god.wallet.publicKey
+      );
+      await ts.zodState.requestInsuranceWithdrawal(godShares, reduceInsuranceAmount);
 
       const [godUsdcChange, usdcVaultChange] = await withBalanceChange(
         ts.god,
         [ts.godUSDC, ts.stateUSDCVault],
         async () => {
           const tx = await ts.zodState.reduceInsurance(
+            ts.state.cache.pubkey,
+            reduceInsuranceAmount
+          );
+          await ts.god.connection.confirmTransaction(tx, "finalized");
+          console.log("tx ", tx);
+          const tx1 = await ts.zodState.claimInsurance(
             ts.godUSDC,
             SETTINGS.zodStateUSDCVault,
             ts.stateUSDCVault,
             ts.state.signer,
             ts.state.cache.pubkey,
-            ts.zodState.controlPubkey,
-            reduceInsuranceAmount
+            ts.zodState.controlPubkey
           );
-          await ts.god.connection.confirmTransaction(tx, "finalized");
-          console.log("tx ", tx);
+          await ts.god.connection.confirmTransaction(tx1, "finalized");
+          console.log("tx1 ", tx1);
         }
       );
       assert.equal(usdcVaultChange, -reduceInsuranceAmount.toNumber());
@@ -508,6 +549,10 @@ function bankruptcy(ts: any) {
     });
 
     xit("Bob settles Alice's bankruptcy", async () => {
+      // leaves Alice's usdc worth next to nothing, below dust
+      await ts.zodState.setTestOraclePrice(ts.testOracleProgram, 0, ts.usdcMint, new BN(1), -12);
+      const [usdcOracle] = await ts.zodState.getTestOracleAddress(ts.testOracleProgram, ts.usdcMint);
+
       const [zodTokenChange] = await withBalanceChange(
         ts.bob,
         [ts.bobZodMargin.zodTokenAccount],
@@ -515,9 +560,8 @@ function bankruptcy(ts: any) {
           const tx = await ts.bobZodMargin.settleBankruptcy(
             ts.bobZodMargin.zodTokenAccount,
             ts.aliceZodMargin.pubkey,
-            ts.usdcMint,
             ts.state.cache.pubkey,
-            new BN(0)
+            [usdcOracle]
           );
           await ts.bob.connection.confirmTransaction(tx, "finalized");
           console.log("tx ", tx);
@@ -541,6 +585,9 @@ function bankruptcy(ts: any) {
       // await ts.god.connection.confirmTransaction(tx1, "finalized");
       // console.log("tx1 ", tx1);
 
+      await ts.zodState.setTestOraclePrice(ts.testOracleProgram, 0, ts.usdcMint, new BN(1), 0);
+      const [usdcOracle] = await ts.zodState.getTestOracleAddress(ts.testOracleProgram, ts.usdcMint);
+
       const depositAmount = new BN(100 * 10 ** USDC_DECIMALS);
 
       const tx2 = await ts.aliceZodMargin.depositRaw(
@@ -559,13 +606,18 @@ function bankruptcy(ts: any) {
       const tx3 = await ts.aliceZodMargin.mintRaw(
         ts.aliceZodMargin.zodTokenAccount,
         ts.state.cache.pubkey,
-        mintAmount
+        mintAmount,
+        [usdcOracle]
       );
       await ts.alice.connection.confirmTransaction(tx3, "finalized");
       console.log("tx3 ", tx3);
     });
 
     xit("Bob settles Alice's bankruptcy when insurance is zero", async () => {
+      // leaves Alice's usdc worth next to nothing, below dust
+      await ts.zodState.setTestOraclePrice(ts.testOracleProgram, 0, ts.usdcMint, new BN(1), -12);
+      const [usdcOracle] = await ts.zodState.getTestOracleAddress(ts.testOracleProgram, ts.usdcMint);
+
       const [zodTokenChange] = await withBalanceChange(
         ts.bob,
         [ts.bobZodMargin.zodTokenAccount],
@@ -573,9 +625,8 @@ function bankruptcy(ts: any) {
           const tx = await ts.bobZodMargin.settleBankruptcy(
             ts.bobZodMargin.zodTokenAccount,
             ts.aliceZodMargin.pubkey,
-            ts.usdcMint,
             ts.state.cache.pubkey,
-            new BN(0)
+            [usdcOracle]
           );
           await ts.bob.connection.confirmTransaction(tx, "finalized");
           console.log("tx ", tx);
@@ -586,6 +637,168 @@ function bankruptcy(ts: any) {
   });
 }
 
+// a fresh wallet with sol for fees, usdc to deposit and its own zodMargin
+async function createFundedMargin(ts: any, depositAmount: BN) {
+  const acc = Keypair.generate();
+
+  await ts.god.send(
+    new Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: ts.god.wallet.publicKey,
+        toPubkey: acc.publicKey,
+        lamports: LAMPORTS_PER_SOL / 10,
+      })
+    )
+  );
+
+  const provider = new anchor.Provider(
+    ts.program.provider.connection,
+    // @ts-ignore
+    new anchor.Wallet(acc),
+    {
+      preflightCommitment: "confirmed",
+      commitment: "confirmed",
+    }
+  );
+  // @ts-ignore
+  const program = new anchor.Program(
+    ts.program.idl,
+    ts.program.programId,
+    provider
+  );
+  // @ts-ignore
+  const zodProgram = new anchor.Program(
+    ts.zodProgram.idl,
+    ts.zodProgram.programId,
+    provider
+  );
+
+  const usdc = await createTokenAccount(ts.god, ts.usdcMint, acc.publicKey);
+  await ts.god.send(
+    new Transaction().add(
+      Token.createTransferInstruction(
+        TOKEN_PROGRAM_ID,
+        ts.godUSDC,
+        usdc,
+        ts.god.wallet.publicKey,
+        [],
+        depositAmount.toNumber()
+      )
+    )
+  );
+
+  const zodMargin = await ZodMargin.create(
+    zodProgram,
+    program,
+    ts.zodState,
+    ts.state,
+    ts.state.cache
+  );
+
+  await zodMargin.depositRaw(
+    usdc,
+    SETTINGS.zodStateUSDCVault,
+    ts.stateUSDCVault,
+    ts.state.signer,
+    ts.state.cache.pubkey,
+    depositAmount
+  );
+
+  return { provider, zodProgram, usdc, zodMargin };
+}
+
+// withdraw requires collateral strictly above the amount, so one smol of dust stays behind
+async function withdrawAllButDust(ts: any, usdc: PublicKey, zodMargin: ZodMargin, depositAmount: BN) {
+  await ts.zodState.updateOracleHistory();
+  await zodMargin.withdrawRaw(
+    usdc,
+    SETTINGS.zodStateUSDCVault,
+    ts.stateUSDCVault,
+    ts.state.signer,
+    ts.state.cache.pubkey,
+    ts.zodState.controlPubkey,
+    depositAmount.subn(1)
+  );
+}
+
+function closeMargin(ts: any) {
+  describe("calling close_margin.rs", async () => {
+    it("creates, empties and closes Carol's zodMargin", async () => {
+      const depositAmount = new BN(1 * 10 ** USDC_DECIMALS);
+      const { provider, zodProgram, usdc, zodMargin } =
+        await createFundedMargin(ts, depositAmount);
+
+      await withdrawAllButDust(ts, usdc, zodMargin, depositAmount);
+
+      const tx = await zodMargin.close(ts.state.cache.pubkey, true);
+      await provider.connection.confirmTransaction(tx, "finalized");
+      console.log("tx ", tx);
+
+      assert.equal(
+        await zodProgram.account.zodMargin.fetchNullable(zodMargin.pubkey),
+        null
+      );
+      assert.equal(
+        await provider.connection.getAccountInfo(zodMargin.zodTokenAccount),
+        null
+      );
+    });
+
+    it("mints, repays in full and closes Dave's zodMargin", async () => {
+      const depositAmount = new BN(1 * 10 ** USDC_DECIMALS);
+      const mintAmount = new BN(0.1 * 10 ** USDC_DECIMALS);
+      // covers the stability fee accrued between the mint and the burn
+      const feeBuffer = 1000;
+      const { provider, zodProgram, usdc, zodMargin } =
+        await createFundedMargin(ts, depositAmount);
+
+      await ts.zodState.updateOracleHistory();
+      await zodMargin.mintRaw(
+        zodMargin.zodTokenAccount,
+        ts.state.cache.pubkey,
+        mintAmount
+      );
+      await ts.alice.send(
+        new Transaction().add(
+          Token.createTransferInstruction(
+            TOKEN_PROGRAM_ID,
+            ts.aliceZodMargin.zodTokenAccount,
+            zodMargin.zodTokenAccount,
+            ts.alice.wallet.publicKey,
+            [],
+            feeBuffer
+          )
+        )
+      );
+
+      // anything at or above the debt burns its ceiling and zeroes the margin
+      await zodMargin.burnRaw(
+        zodMargin.zodTokenAccount,
+        ts.state.cache.pubkey,
+        new BN("18446744073709551615")
+      );
+      const margin = await zodProgram.account.zodMargin.fetch(zodMargin.pubkey);
+      assert.equal(margin.zodBalance.data.toString(), "0");
+      const left = await provider.connection.getTokenAccountBalance(
+        zodMargin.zodTokenAccount
+      );
+      assert.ok(Number(left.value.amount) <= feeBuffer);
+
+      await withdrawAllButDust(ts, usdc, zodMargin, depositAmount);
+
+      // the leftover fee buffer stays in the token account, so only the margin is closed
+      const tx = await zodMargin.close(ts.state.cache.pubkey, false);
+      await provider.connection.confirmTransaction(tx, "finalized");
+      console.log("tx ", tx);
+
+      assert.equal(
+        await zodProgram.account.zodMargin.fetchNullable(zodMargin.pubkey),
+        null
+      );
+    });
+  });
+}
+
 function fetchZoStateInfo(ts: any) {
   it("Fetching the Zo state stuff", async () => {
     const provider = anchor.Provider.local(SETTINGS.connection, {
@@ -606,6 +819,8 @@ function fetchZoStateInfo(ts: any) {
     //@ts-ignore
     ts.zodProgram = anchor.workspace.Zod as Program<Zod>;
     ts.zodProgram = new Program(idl as Idl, SETTINGS.zodPid, ts.god);
+    // localnet only, see the zod-test-oracle program
+    ts.testOracleProgram = anchor.workspace.ZodTestOracle as Program;
 
     ts.usdcMint = SETTINGS.usdcMint;
     ts.btcMint = SETTINGS.btcMint;
@@ -624,5 +839,7 @@ describe("init zod e2e", async () => {
 
   zodAlice(testState);
 
+  closeMargin(testState);
+
   bankruptcy(testState);
 });
@@ -0,0 +1,123 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::ZeroCopy;
+use std::mem::size_of;
+
+use zod::state::{ZodInsuranceWithdrawal, ZodLiquidationAuction, ZodMargin, ZodOracleHistory, ZodState};
+#[cfg(feature = "test-oracle")]
+use zod_test_oracle::TestOracle;
+
+// reads a zero copy account from raw account data, checking its discriminator
+pub fn load_zero_copy<T: ZeroCopy>(data: &[u8]) -> Result<T, ProgramError> {
+    if data.len() < 8 {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    if data[..8] != T::discriminator() {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    let end = 8 + size_of::<T>();
+    if data.len() < end {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    Ok(*bytemuck::from_bytes(&data[8..end]))
+}
+
+pub fn load_zod_state(data: &[u8]) -> Result<ZodState, ProgramError> {
+    load_zero_copy(data)
+}
+
+pub fn load_zod_margin(data: &[u8]) -> Result<ZodMargin, ProgramError> {
+    load_zero_copy(data)
+}
+
+pub fn load_auction(data: &[u8]) -> Result<ZodLiquidationAuction, ProgramError> {
+    load_zero_copy(data)
+}
+
+pub fn load_oracle_history(data: &[u8]) -> Result<ZodOracleHistory, ProgramError> {
+    load_zero_copy(data)
+}
+
+pub fn load_insurance_withdrawal(data: &[u8]) -> Result<ZodInsuranceWithdrawal, ProgramError> {
+    ZodInsuranceWithdrawal::try_deserialize(&mut &data[..])
+}
+
+#[cfg(feature = "test-oracle")]
+pub fn load_test_oracle(data: &[u8]) -> Result<TestOracle, ProgramError> {
+    TestOracle::try_deserialize(&mut &data[..])
+}
+
+// the 01 accounts a ZodClient is built from
+
+pub fn load_zo_state(data: &[u8]) -> Result<zo::State, ProgramError> {
+    load_zero_copy(data)
+}
+
+pub fn load_zo_margin(data: &[u8]) -> Result<zo::Margin, ProgramError> {
+    load_zero_copy(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+    use bytemuck::Zeroable;
+
+    fn history_data() -> Vec<u8> {
+        let mut history = ZodOracleHistory::zeroed();
+        history.period = 600;
+        let mut data = ZodOracleHistory::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&history));
+        data
+    }
+
+    #[test]
+    fn test_load_zero_copy() {
+        let history = load_oracle_history(&history_data()).unwrap();
+        assert_eq!({ history.period }, 600);
+
+        // trailing bytes past the struct are ignored, like the program's own loader
+        let mut data = history_data();
+        data.extend_from_slice(&[0; 16]);
+        assert!(load_oracle_history(&data).is_ok());
+    }
+
+    #[test]
+    fn test_load_zero_copy_rejects_short_data() {
+        assert_eq!(
+            load_oracle_history(&[]).unwrap_err(),
+            ErrorCode::AccountDiscriminatorNotFound.into()
+        );
+        assert_eq!(
+            load_oracle_history(&history_data()[..7]).unwrap_err(),
+            ErrorCode::AccountDiscriminatorNotFound.into()
+        );
+
+        let data = history_data();
+        assert_eq!(
+            load_oracle_history(&data[..data.len() - 1]).unwrap_err(),
+            ErrorCode::AccountDidNotDeserialize.into()
+        );
+    }
+
+    #[test]
+    fn test_load_zero_copy_rejects_other_accounts() {
+        // a real oracle history read as a margin or auction
+        let data = history_data();
+        assert_eq!(
+            load_zod_margin(&data).unwrap_err(),
+            ErrorCode::AccountDiscriminatorMismatch.into()
+        );
+        assert_eq!(
+            load_auction(&data).unwrap_err(),
+            ErrorCode::AccountDiscriminatorMismatch.into()
+        );
+
+        let mut data = history_data();
+        data[0] ^= 1;
+        assert_eq!(
+            load_oracle_history(&data).unwrap_err(),
+            ErrorCode::AccountDiscriminatorMismatch.into()
+        );
+    }
+}
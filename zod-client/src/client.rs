@@ -0,0 +1,1103 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+
+use zod::instructions::LiquidationLeg;
+use zod::state::ZodState;
+use zod::{accounts, instruction};
+
+use crate::pda::*;
+
+// everything an instruction needs besides the caller's own accounts, taken from the zod state
+// and the 01 state and margin behind it. rebuild it after vaults are added or the admin changes
+pub struct ZodClient {
+    pub zod_state: Pubkey,
+    pub state: ZodState,
+    pub zo_state: Pubkey,
+    pub zo_cache: Pubkey,
+    pub zo_state_signer: Pubkey,
+    pub zo_margin: Pubkey,
+    pub zo_control: Pubkey,
+    pub zo_vaults: [Pubkey; 25],
+    pub collateral_mints: [Pubkey; 25],
+}
+
+impl ZodClient {
+    pub fn new(state: ZodState, zo_state: &zo::State, zo_margin: &zo::Margin) -> Self {
+        let mut collateral_mints = [Pubkey::default(); 25];
+        for (i, mint) in collateral_mints.iter_mut().enumerate() {
+            *mint = zo_state.collaterals[i].mint;
+        }
+
+        Self {
+            zod_state: get_zod_state_address().0,
+            zo_state: state.zo_program_state,
+            zo_cache: zo_state.cache,
+            zo_state_signer: get_zo_state_signer_address(&state.zo_program_state).0,
+            zo_margin: state.zo_program_margin,
+            zo_control: zo_margin.control,
+            zo_vaults: zo_state.vaults,
+            collateral_mints,
+            state,
+        }
+    }
+
+    pub fn zod_mint(&self) -> Pubkey {
+        self.state.zod_token_info.mint
+    }
+
+    pub fn get_zod_margin(&self, authority: &Pubkey) -> Pubkey {
+        get_zod_margin_address(authority, &self.zod_state).0
+    }
+
+    pub fn get_oracle_history(&self) -> Pubkey {
+        get_oracle_history_address(&self.zod_state).0
+    }
+
+    // pyth, switchboard and test oracle accounts of collaterals not priced from the 01 cache,
+    // for instructions that value collateral. the 01 cache is always passed already
+    pub fn get_oracles(&self) -> Vec<Pubkey> {
+        let configs = self.state.oracle_configs;
+        let mut oracles: Vec<Pubkey> = Vec::new();
+        for config in configs.iter() {
+            for key in [{ config.account }, { config.deviation_account }] {
+                if key != Pubkey::default() && !oracles.contains(&key) {
+                    oracles.push(key);
+                }
+            }
+        }
+        oracles
+    }
+
+    fn build(
+        &self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+        remaining_accounts: &[Pubkey],
+    ) -> Instruction {
+        let mut metas = accounts.to_account_metas(None);
+        metas.extend(
+            remaining_accounts
+                .iter()
+                .map(|key| AccountMeta::new_readonly(*key, false)),
+        );
+        Instruction {
+            program_id: zod::ID,
+            accounts: metas,
+            data: data.data(),
+        }
+    }
+
+    // setup
+
+    // control and mint are new accounts the caller creates in the same transaction, owned by 01 and
+    // the token program respectively. there is no zod state to build a client from yet
+    pub fn init_zod_state(admin: &Pubkey, zo_state: &Pubkey, control: &Pubkey, mint: &Pubkey) -> Instruction {
+        let (zod_state, zod_state_nonce) = get_zod_state_address();
+        let (zo_margin, zo_margin_nonce) = get_zo_margin_address(&zod_state, zo_state);
+        let accounts = accounts::InitZodState {
+            admin: *admin,
+            zod_state,
+            zo_program_state: *zo_state,
+            zo_program_margin: zo_margin,
+            zo_program: zo::ID,
+            control: *control,
+            rent: sysvar::rent::ID,
+            zo_program_margin_rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            mint: *mint,
+        };
+        Instruction {
+            program_id: zod::ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::InitZodState {
+                zod_state_nonce,
+                zo_program_nonce: zo_margin_nonce,
+            }
+            .data(),
+        }
+    }
+
+    pub fn add_vaults(&self, vault: &Pubkey, mint: &Pubkey) -> Instruction {
+        self.build(
+            accounts::AddVaults {
+                admin: self.state.admin,
+                zo_state: self.zo_state,
+                zod_state: self.zod_state,
+                vault: *vault,
+                mint: *mint,
+            },
+            instruction::AddVaults {},
+            &[],
+        )
+    }
+
+    // margins
+
+    pub fn create_zod_margin(&self, payer: &Pubkey, authority: &Pubkey) -> Instruction {
+        let (margin, nonce) = get_zod_margin_address(authority, &self.zod_state);
+        self.build(
+            accounts::CreateZodMargin {
+                zod_state: self.zod_state,
+                payer: *payer,
+                authority: *authority,
+                margin,
+                token_account: get_associated_token_address(authority, &self.zod_mint()),
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                mint: self.zod_mint(),
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateZodMargin { nonce },
+            &[],
+        )
+    }
+
+    pub fn close_zod_margin(
+        &self,
+        authority: &Pubkey,
+        zod_token_account: &Pubkey,
+        close_token_account: bool,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::CloseZodMargin {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                authority: *authority,
+                margin: self.get_zod_margin(authority),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+            },
+            instruction::CloseZodMargin { close_token_account },
+            oracles,
+        )
+    }
+
+    pub fn zod_deposit(
+        &self,
+        authority: &Pubkey,
+        token_account: &Pubkey,
+        collateral_index: usize,
+        amount: u64,
+    ) -> Instruction {
+        self.build(
+            accounts::ZodDeposit {
+                zod_state: self.zod_state,
+                zo_program_margin: self.zo_margin,
+                zo_program: zo::ID,
+                zo_program_state: self.zo_state,
+                state_signer: self.zo_state_signer,
+                cache: self.zo_cache,
+                authority: *authority,
+                zod_margin: self.get_zod_margin(authority),
+                token_account: *token_account,
+                zo_vault: self.zo_vaults[collateral_index],
+                zod_vault: self.state.vaults[collateral_index],
+                token_program: token::ID,
+            },
+            instruction::ZodDeposit { amount },
+            &[],
+        )
+    }
+
+    pub fn zod_withdraw(
+        &self,
+        authority: &Pubkey,
+        token_account: &Pubkey,
+        zod_token_account: &Pubkey,
+        collateral_index: usize,
+        amount: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::ZodWithdraw {
+                zo_program_margin: self.zo_margin,
+                zo_program: zo::ID,
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                state_signer: self.zo_state_signer,
+                cache: self.zo_cache,
+                authority: *authority,
+                zod_margin: self.get_zod_margin(authority),
+                oracle_history: self.get_oracle_history(),
+                control: self.zo_control,
+                token_account: *token_account,
+                zo_vault: self.zo_vaults[collateral_index],
+                zod_account: *zod_token_account,
+                zod_vault: self.state.vaults[collateral_index],
+                token_program: token::ID,
+            },
+            instruction::ZodWithdraw { amount },
+            oracles,
+        )
+    }
+
+    pub fn zod_mint(
+        &self,
+        authority: &Pubkey,
+        zod_token_account: &Pubkey,
+        amount: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::ZodMint {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                authority: *authority,
+                zod_margin: self.get_zod_margin(authority),
+                oracle_history: self.get_oracle_history(),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+                mint: self.zod_mint(),
+            },
+            instruction::ZodMint { amount },
+            oracles,
+        )
+    }
+
+    // an amount at or above the margin's debt, e.g. u64::MAX, repays it in full
+    pub fn zod_burn(&self, authority: &Pubkey, zod_token_account: &Pubkey, amount: u64) -> Instruction {
+        self.build(
+            accounts::ZodBurn {
+                zod_state: self.zod_state,
+                authority: *authority,
+                zod_margin: self.get_zod_margin(authority),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+                mint: self.zod_mint(),
+            },
+            instruction::ZodBurn { amount },
+            &[],
+        )
+    }
+
+    // candidates are other margins the target has to be at least as risky as
+    pub fn redeem_zod(
+        &self,
+        redeemer: &Pubkey,
+        target_zod_margin: &Pubkey,
+        collateral_index: usize,
+        zod_token_account: &Pubkey,
+        amount: u64,
+        candidates: &[Pubkey],
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        let remaining_accounts: Vec<Pubkey> = candidates.iter().chain(oracles).copied().collect();
+        self.build(
+            accounts::RedeemZod {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                redeemer: *redeemer,
+                redeemer_zod_margin: self.get_zod_margin(redeemer),
+                target_zod_margin: *target_zod_margin,
+                collateral_mint: self.collateral_mints[collateral_index],
+                zod_mint: self.zod_mint(),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+            },
+            instruction::RedeemZod { amount },
+            &remaining_accounts,
+        )
+    }
+
+    // liquidations
+
+    pub fn liquidate_zod_position(
+        &self,
+        liqor: &Pubkey,
+        liqee_zod_margin: &Pubkey,
+        zod_token_account: &Pubkey,
+        legs: Vec<LiquidationLeg>,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::LiquidateZodPosition {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                liqor: *liqor,
+                liqor_zod_margin: self.get_zod_margin(liqor),
+                liqee_zod_margin: *liqee_zod_margin,
+                auction: get_auction_address(liqee_zod_margin, &self.zod_state).0,
+                zod_mint: self.zod_mint(),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+            },
+            instruction::LiquidateZodPosition { legs },
+            oracles,
+        )
+    }
+
+    pub fn start_liquidation_auction(
+        &self,
+        keeper: &Pubkey,
+        liqee_zod_margin: &Pubkey,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        let (auction, nonce) = get_auction_address(liqee_zod_margin, &self.zod_state);
+        self.build(
+            accounts::StartLiquidationAuction {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                keeper: *keeper,
+                liqee_zod_margin: *liqee_zod_margin,
+                auction,
+                system_program: system_program::ID,
+            },
+            instruction::StartLiquidationAuction { nonce },
+            oracles,
+        )
+    }
+
+    pub fn bid_auction(
+        &self,
+        bidder: &Pubkey,
+        liqee_zod_margin: &Pubkey,
+        zod_token_account: &Pubkey,
+        collateral_index: u8,
+        amount: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::BidAuction {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                bidder: *bidder,
+                bidder_zod_margin: self.get_zod_margin(bidder),
+                liqee_zod_margin: *liqee_zod_margin,
+                auction: get_auction_address(liqee_zod_margin, &self.zod_state).0,
+                zod_mint: self.zod_mint(),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+            },
+            instruction::BidAuction {
+                collateral_index,
+                amount,
+            },
+            oracles,
+        )
+    }
+
+    // keeper is whoever started the auction, it gets the auction's rent back
+    pub fn end_liquidation_auction(
+        &self,
+        keeper: &Pubkey,
+        liqee_zod_margin: &Pubkey,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::EndLiquidationAuction {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                keeper: *keeper,
+                liqee_zod_margin: *liqee_zod_margin,
+                auction: get_auction_address(liqee_zod_margin, &self.zod_state).0,
+            },
+            instruction::EndLiquidationAuction {},
+            oracles,
+        )
+    }
+
+    pub fn zod_settle_bankruptcy(
+        &self,
+        liqor: &Pubkey,
+        liqee_zod_margin: &Pubkey,
+        zod_token_account: &Pubkey,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::SettleZodBankruptcy {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                liqor: *liqor,
+                liqor_zod_margin: self.get_zod_margin(liqor),
+                liqee_zod_margin: *liqee_zod_margin,
+                zod_mint: self.zod_mint(),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+            },
+            instruction::ZodSettleBankruptcy {},
+            oracles,
+        )
+    }
+
+    // insurance
+
+    pub fn init_insurance_shares(
+        &self,
+        share_mint: &Pubkey,
+        share_account: &Pubkey,
+        cooldown: u64,
+        withdraw_window: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::InitInsuranceShares {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                share_mint: *share_mint,
+                share_account: *share_account,
+                token_program: token::ID,
+            },
+            instruction::InitInsuranceShares {
+                cooldown,
+                withdraw_window,
+            },
+            oracles,
+        )
+    }
+
+    pub fn zod_add_insurance(
+        &self,
+        authority: &Pubkey,
+        token_account: &Pubkey,
+        collateral_index: usize,
+        amount: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::ZodAddInsurance {
+                zod_state: self.zod_state,
+                zo_program_margin: self.zo_margin,
+                zo_program: zo::ID,
+                zo_program_state: self.zo_state,
+                state_signer: self.zo_state_signer,
+                cache: self.zo_cache,
+                authority: *authority,
+                token_account: *token_account,
+                zo_vault: self.zo_vaults[collateral_index],
+                zod_vault: self.state.vaults[collateral_index],
+                share_mint: self.state.insurance_share_mint,
+                share_account: get_associated_token_address(authority, &self.state.insurance_share_mint),
+                token_program: token::ID,
+            },
+            instruction::ZodAddInsurance { amount },
+            oracles,
+        )
+    }
+
+    pub fn request_insurance_withdrawal(&self, authority: &Pubkey, shares: u64) -> Instruction {
+        let (withdrawal, nonce) = get_insurance_withdrawal_address(authority, &self.zod_state);
+        self.build(
+            accounts::RequestInsuranceWithdrawal {
+                zod_state: self.zod_state,
+                authority: *authority,
+                share_mint: self.state.insurance_share_mint,
+                share_account: get_associated_token_address(authority, &self.state.insurance_share_mint),
+                withdrawal,
+                escrow: get_insurance_escrow_address(&withdrawal).0,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::RequestInsuranceWithdrawal { nonce, shares },
+            &[],
+        )
+    }
+
+    // hands escrowed shares back to the authority's share ata, the rest keep their cooldown
+    pub fn cancel_insurance_withdrawal(&self, authority: &Pubkey, shares: u64) -> Instruction {
+        let withdrawal = get_insurance_withdrawal_address(authority, &self.zod_state).0;
+        self.build(
+            accounts::CancelInsuranceWithdrawal {
+                zod_state: self.zod_state,
+                authority: *authority,
+                share_account: get_associated_token_address(authority, &self.state.insurance_share_mint),
+                withdrawal,
+                escrow: get_insurance_escrow_address(&withdrawal).0,
+                token_program: token::ID,
+            },
+            instruction::CancelInsuranceWithdrawal { shares },
+            &[],
+        )
+    }
+
+    // pays out the same fraction of every insurance collateral, each claimed with claim_insurance
+    pub fn zod_reduce_insurance(
+        &self,
+        authority: &Pubkey,
+        shares: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        let withdrawal = get_insurance_withdrawal_address(authority, &self.zod_state).0;
+        self.build(
+            accounts::ZodReduceInsurance {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                authority: *authority,
+                share_mint: self.state.insurance_share_mint,
+                withdrawal,
+                escrow: get_insurance_escrow_address(&withdrawal).0,
+                token_program: token::ID,
+            },
+            instruction::ZodReduceInsurance { shares },
+            oracles,
+        )
+    }
+
+    pub fn claim_insurance(
+        &self,
+        authority: &Pubkey,
+        token_account: &Pubkey,
+        collateral_index: usize,
+    ) -> Instruction {
+        self.build(
+            accounts::ClaimInsurance {
+                zo_program_margin: self.zo_margin,
+                zo_program: zo::ID,
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                state_signer: self.zo_state_signer,
+                cache: self.zo_cache,
+                authority: *authority,
+                control: self.zo_control,
+                token_account: *token_account,
+                zo_vault: self.zo_vaults[collateral_index],
+                zod_vault: self.state.vaults[collateral_index],
+                withdrawal: get_insurance_withdrawal_address(authority, &self.zod_state).0,
+                token_program: token::ID,
+            },
+            instruction::ClaimInsurance {},
+            &[],
+        )
+    }
+
+    // psm
+
+    pub fn init_psm(
+        &self,
+        psm_mint: &Pubkey,
+        psm_vault: &Pubkey,
+        tin: u16,
+        tout: u16,
+        debt_ceiling: u64,
+    ) -> Instruction {
+        self.build(
+            accounts::InitPsm {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                psm_mint: *psm_mint,
+                psm_vault: *psm_vault,
+            },
+            instruction::InitPsm {
+                tin,
+                tout,
+                debt_ceiling,
+            },
+            &[],
+        )
+    }
+
+    pub fn update_psm(&self, tin: u16, tout: u16, debt_ceiling: u64) -> Instruction {
+        self.build(
+            accounts::UpdatePsm {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::UpdatePsm {
+                tin,
+                tout,
+                debt_ceiling,
+            },
+            &[],
+        )
+    }
+
+    pub fn psm_swap_in(
+        &self,
+        authority: &Pubkey,
+        token_account: &Pubkey,
+        zod_token_account: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        self.build(
+            accounts::PsmSwapIn {
+                zod_state: self.zod_state,
+                authority: *authority,
+                token_account: *token_account,
+                psm_vault: self.state.psm_vault,
+                zod_token_account: *zod_token_account,
+                mint: self.zod_mint(),
+                token_program: token::ID,
+            },
+            instruction::PsmSwapIn { amount },
+            &[],
+        )
+    }
+
+    pub fn psm_swap_out(
+        &self,
+        authority: &Pubkey,
+        zod_token_account: &Pubkey,
+        token_account: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        self.build(
+            accounts::PsmSwapOut {
+                zod_state: self.zod_state,
+                authority: *authority,
+                zod_token_account: *zod_token_account,
+                psm_vault: self.state.psm_vault,
+                token_account: *token_account,
+                mint: self.zod_mint(),
+                token_program: token::ID,
+            },
+            instruction::PsmSwapOut { amount },
+            &[],
+        )
+    }
+
+    pub fn collect_psm_fees(&self, token_account: &Pubkey) -> Instruction {
+        self.build(
+            accounts::CollectPsmFees {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                psm_vault: self.state.psm_vault,
+                token_account: *token_account,
+                token_program: token::ID,
+            },
+            instruction::CollectPsmFees {},
+            &[],
+        )
+    }
+
+    // flash mints, flash_repay has to come later in the same transaction
+
+    pub fn flash_mint(&self, zod_token_account: &Pubkey, amount: u64) -> Instruction {
+        self.build(
+            accounts::FlashMint {
+                zod_state: self.zod_state,
+                mint: self.zod_mint(),
+                token_account: *zod_token_account,
+                instructions: sysvar::instructions::ID,
+                token_program: token::ID,
+            },
+            instruction::FlashMint { amount },
+            &[],
+        )
+    }
+
+    pub fn flash_repay(&self, authority: &Pubkey, zod_token_account: &Pubkey) -> Instruction {
+        self.build(
+            accounts::FlashRepay {
+                zod_state: self.zod_state,
+                authority: *authority,
+                mint: self.zod_mint(),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+            },
+            instruction::FlashRepay {},
+            &[],
+        )
+    }
+
+    // savings
+
+    pub fn init_savings(&self, savings_mint: &Pubkey, savings_vault: &Pubkey, yield_share: u16) -> Instruction {
+        self.build(
+            accounts::InitSavings {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                savings_mint: *savings_mint,
+                savings_vault: *savings_vault,
+            },
+            instruction::InitSavings { yield_share },
+            &[],
+        )
+    }
+
+    // drips pending savings yield first, so the oracles of every collateral with yield are needed
+    pub fn savings_deposit(
+        &self,
+        authority: &Pubkey,
+        zod_token_account: &Pubkey,
+        amount: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::SavingsDeposit {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                mint: self.zod_mint(),
+                authority: *authority,
+                token_account: *zod_token_account,
+                savings_vault: self.state.savings_vault,
+                savings_mint: self.state.savings_mint,
+                share_account: get_associated_token_address(authority, &self.state.savings_mint),
+                token_program: token::ID,
+            },
+            instruction::SavingsDeposit { amount },
+            oracles,
+        )
+    }
+
+    // drips pending savings yield first, so the oracles of every collateral with yield are needed
+    pub fn savings_withdraw(
+        &self,
+        authority: &Pubkey,
+        zod_token_account: &Pubkey,
+        shares: u64,
+        oracles: &[Pubkey],
+    ) -> Instruction {
+        self.build(
+            accounts::SavingsWithdraw {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                mint: self.zod_mint(),
+                authority: *authority,
+                token_account: *zod_token_account,
+                savings_vault: self.state.savings_vault,
+                savings_mint: self.state.savings_mint,
+                share_account: get_associated_token_address(authority, &self.state.savings_mint),
+                token_program: token::ID,
+            },
+            instruction::SavingsWithdraw { shares },
+            oracles,
+        )
+    }
+
+    pub fn drip_savings(&self, oracles: &[Pubkey]) -> Instruction {
+        self.build(
+            accounts::DripSavings {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                mint: self.zod_mint(),
+                savings_vault: self.state.savings_vault,
+                token_program: token::ID,
+            },
+            instruction::DripSavings {},
+            oracles,
+        )
+    }
+
+    // fees
+
+    pub fn collect_stability_fees(&self, zod_token_account: &Pubkey) -> Instruction {
+        self.build(
+            accounts::CollectStabilityFees {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                mint: self.zod_mint(),
+                token_account: *zod_token_account,
+                token_program: token::ID,
+            },
+            instruction::CollectStabilityFees {},
+            &[],
+        )
+    }
+
+    pub fn collect_fees(&self, token_account: &Pubkey, collateral_index: usize) -> Instruction {
+        self.build(
+            accounts::CollectFees {
+                zo_program_margin: self.zo_margin,
+                zo_program: zo::ID,
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                state_signer: self.zo_state_signer,
+                cache: self.zo_cache,
+                admin: self.state.admin,
+                control: self.zo_control,
+                token_account: *token_account,
+                zo_vault: self.zo_vaults[collateral_index],
+                zod_vault: self.state.vaults[collateral_index],
+                token_program: token::ID,
+            },
+            instruction::CollectFees {},
+            &[],
+        )
+    }
+
+    // oracles
+
+    // oracle and deviation_oracle can be any account when their source is the 01 cache
+    pub fn set_oracle_source(
+        &self,
+        collateral_index: u8,
+        source: u8,
+        oracle: &Pubkey,
+        deviation_source: u8,
+        deviation_oracle: &Pubkey,
+    ) -> Instruction {
+        self.build(
+            accounts::SetOracleSource {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                oracle: *oracle,
+                deviation_oracle: *deviation_oracle,
+            },
+            instruction::SetOracleSource {
+                collateral_index,
+                source,
+                deviation_source,
+            },
+            &[],
+        )
+    }
+
+    pub fn set_oracle_guards(&self, max_confidence: u16, max_deviation: u16) -> Instruction {
+        self.build(
+            accounts::SetOracleGuards {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetOracleGuards {
+                max_confidence,
+                max_deviation,
+            },
+            &[],
+        )
+    }
+
+    pub fn init_oracle_history(&self, period: u64) -> Instruction {
+        let (oracle_history, nonce) = get_oracle_history_address(&self.zod_state);
+        self.build(
+            accounts::InitOracleHistory {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                oracle_history,
+                system_program: system_program::ID,
+            },
+            instruction::InitOracleHistory { nonce, period },
+            &[],
+        )
+    }
+
+    pub fn update_oracle_history(&self, oracles: &[Pubkey]) -> Instruction {
+        self.build(
+            accounts::UpdateOracleHistory {
+                zod_state: self.zod_state,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+                oracle_history: self.get_oracle_history(),
+            },
+            instruction::UpdateOracleHistory {},
+            oracles,
+        )
+    }
+
+    // writes a zod-test-oracle price, only read by zod programs built with the test-oracle feature
+    // once set_oracle_source points the collateral at get_test_oracle_address
+    #[cfg(feature = "test-oracle")]
+    pub fn set_test_oracle_price(
+        &self,
+        authority: &Pubkey,
+        collateral_index: usize,
+        price: i64,
+        conf: u64,
+        expo: i32,
+    ) -> Instruction {
+        let mint = self.collateral_mints[collateral_index];
+        let (oracle, nonce) = get_test_oracle_address(authority, &mint);
+        let accounts = zod_test_oracle::accounts::SetPrice {
+            authority: *authority,
+            mint,
+            oracle,
+            system_program: system_program::ID,
+        };
+        Instruction {
+            program_id: zod_test_oracle::ID,
+            accounts: accounts.to_account_metas(None),
+            data: zod_test_oracle::instruction::SetPrice {
+                nonce,
+                price,
+                conf,
+                expo,
+            }
+            .data(),
+        }
+    }
+
+    // admin
+
+    pub fn update_zod_params(
+        &self,
+        weight: u16,
+        liq_fee: u16,
+        optimal_util: u16,
+        optimal_rate: u16,
+        max_rate: u16,
+        og_fee: u16,
+    ) -> Instruction {
+        self.build(
+            accounts::UpdateZodParams {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::UpdateZodParams {
+                weight,
+                liq_fee,
+                optimal_util,
+                optimal_rate,
+                max_rate,
+                og_fee,
+            },
+            &[],
+        )
+    }
+
+    pub fn propose_admin(&self, new_admin: &Pubkey) -> Instruction {
+        self.build(
+            accounts::ProposeAdmin {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::ProposeAdmin { new_admin: *new_admin },
+            &[],
+        )
+    }
+
+    pub fn accept_admin(&self) -> Instruction {
+        self.build(
+            accounts::AcceptAdmin {
+                zod_state: self.zod_state,
+                pending_admin: self.state.pending_admin,
+            },
+            instruction::AcceptAdmin {},
+            &[],
+        )
+    }
+
+    // the program only accepts a delay at or above the current one
+    pub fn set_admin_timelock(&self, delay: u64) -> Instruction {
+        self.build(
+            accounts::SetAdminTimelock {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetAdminTimelock { delay },
+            &[],
+        )
+    }
+
+    // authority is the admin or the guardian, which can only add flags
+    pub fn set_pause_flags(&self, authority: &Pubkey, flags: u16) -> Instruction {
+        self.build(
+            accounts::SetPauseFlags {
+                zod_state: self.zod_state,
+                authority: *authority,
+            },
+            instruction::SetPauseFlags { flags },
+            &[],
+        )
+    }
+
+    pub fn set_guardian(&self, guardian: &Pubkey) -> Instruction {
+        self.build(
+            accounts::SetGuardian {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetGuardian { guardian: *guardian },
+            &[],
+        )
+    }
+
+    pub fn set_collateral_cap(&self, collateral_index: u8, cap: u64) -> Instruction {
+        self.build(
+            accounts::SetCollateralCap {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                zo_program_state: self.zo_state,
+            },
+            instruction::SetCollateralCap {
+                collateral_index,
+                cap,
+            },
+            &[],
+        )
+    }
+
+    pub fn set_debt_ceiling(&self, ceiling: u64) -> Instruction {
+        self.build(
+            accounts::SetDebtCeiling {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetDebtCeiling { ceiling },
+            &[],
+        )
+    }
+
+    pub fn set_stability_fee(&self, annual_rate_bps: u16) -> Instruction {
+        self.build(
+            accounts::SetStabilityFee {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetStabilityFee { annual_rate_bps },
+            &[],
+        )
+    }
+
+    pub fn set_flash_mint_fee(&self, fee: u16) -> Instruction {
+        self.build(
+            accounts::SetFlashMintFee {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetFlashMintFee { fee },
+            &[],
+        )
+    }
+
+    pub fn set_insurance_cooldown(&self, cooldown: u64, withdraw_window: u64) -> Instruction {
+        self.build(
+            accounts::SetInsuranceCooldown {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetInsuranceCooldown {
+                cooldown,
+                withdraw_window,
+            },
+            &[],
+        )
+    }
+
+    pub fn set_min_insurance_ratio(&self, ratio: u16) -> Instruction {
+        self.build(
+            accounts::SetMinInsuranceRatio {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+            },
+            instruction::SetMinInsuranceRatio { ratio },
+            &[],
+        )
+    }
+
+    pub fn set_reserve_ratio(&self, ratio: u16) -> Instruction {
+        self.build(
+            accounts::SetReserveRatio {
+                zod_state: self.zod_state,
+                admin: self.state.admin,
+                zo_program_state: self.zo_state,
+                cache: self.zo_cache,
+            },
+            instruction::SetReserveRatio { ratio },
+            &[],
+        )
+    }
+}
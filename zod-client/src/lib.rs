@@ -0,0 +1,16 @@
+//! Builds zod instructions and reads zod accounts off-chain, so keepers and bots don't have to
+//! hand-assemble account lists. Account metas come from the program's own `accounts` structs,
+//! so they always match what the program expects.
+
+pub mod account;
+pub mod client;
+pub mod pda;
+
+pub use account::*;
+pub use client::ZodClient;
+pub use pda::*;
+
+pub use zod::instructions::LiquidationLeg;
+pub use zod::state::{ZodInsuranceWithdrawal, ZodLiquidationAuction, ZodMargin, ZodOracleHistory, ZodState};
+#[cfg(feature = "test-oracle")]
+pub use zod_test_oracle::TestOracle;
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+
+// zod accounts
+
+pub fn get_zod_state_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"zodv13"], &zod::ID)
+}
+
+pub fn get_zod_margin_address(authority: &Pubkey, zod_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[authority.as_ref(), zod_state.as_ref(), b"zodmarginv2"],
+        &zod::ID,
+    )
+}
+
+pub fn get_auction_address(liqee_zod_margin: &Pubkey, zod_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[liqee_zod_margin.as_ref(), zod_state.as_ref(), b"zodauctionv1"],
+        &zod::ID,
+    )
+}
+
+pub fn get_insurance_withdrawal_address(authority: &Pubkey, zod_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[authority.as_ref(), zod_state.as_ref(), b"zodinsurancewithdrawv1"],
+        &zod::ID,
+    )
+}
+
+pub fn get_insurance_escrow_address(withdrawal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[withdrawal.as_ref(), b"zodinsuranceescrowv1"], &zod::ID)
+}
+
+pub fn get_oracle_history_address(zod_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[zod_state.as_ref(), b"zodoraclehistoryv1"], &zod::ID)
+}
+
+// owned by the zod-test-oracle program, one per authority and collateral
+#[cfg(feature = "test-oracle")]
+pub fn get_test_oracle_address(authority: &Pubkey, collateral_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[authority.as_ref(), collateral_mint.as_ref(), b"zodtestoraclev1"],
+        &zod_test_oracle::ID,
+    )
+}
+
+// 01 accounts
+
+// the 01 margin zod holds all collateral in, owned by the zod state
+pub fn get_zo_margin_address(zod_state: &Pubkey, zo_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[zod_state.as_ref(), zo_state.as_ref(), b"marginv1"], &zo::ID)
+}
+
+// owns the 01 vaults
+pub fn get_zo_state_signer_address(zo_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[zo_state.as_ref()], &zo::ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ZodClient;
+    use bytemuck::Zeroable;
+    use zod::state::ZodState;
+
+    // seeds copied from the program's account constraints, so a seed bumped on one side only fails here
+    fn program_address(seeds: &[&[u8]]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(seeds, &zod::ID)
+    }
+
+    fn client() -> ZodClient {
+        ZodClient::new(ZodState::zeroed(), &zo::State::zeroed(), &zo::Margin::zeroed())
+    }
+
+    #[test]
+    fn test_zod_state_address() {
+        assert_eq!(get_zod_state_address(), program_address(&[b"zodv13"]));
+        assert_eq!(client().zod_state, get_zod_state_address().0);
+    }
+
+    #[test]
+    fn test_init_zod_state_metas() {
+        let admin = Pubkey::new_unique();
+        let zo_state = Pubkey::new_unique();
+        let ix = ZodClient::init_zod_state(&admin, &zo_state, &Pubkey::new_unique(), &Pubkey::new_unique());
+        let (zod_state, zod_state_nonce) = program_address(&[b"zodv13"]);
+        let (zo_margin, zo_margin_nonce) =
+            Pubkey::find_program_address(&[zod_state.as_ref(), zo_state.as_ref(), b"marginv1"], &zo::ID);
+
+        assert_eq!(ix.accounts[1].pubkey, zod_state);
+        assert_eq!(ix.accounts[3].pubkey, zo_margin);
+        assert_eq!(ix.data[8..10], [zod_state_nonce, zo_margin_nonce]);
+    }
+
+    #[test]
+    fn test_create_zod_margin_metas() {
+        let client = client();
+        let authority = Pubkey::new_unique();
+        let ix = client.create_zod_margin(&Pubkey::new_unique(), &authority);
+        let (margin, nonce) = program_address(&[authority.as_ref(), client.zod_state.as_ref(), b"zodmarginv2"]);
+
+        assert_eq!(ix.program_id, zod::ID);
+        assert_eq!(ix.accounts[0].pubkey, client.zod_state);
+        assert_eq!(ix.accounts[3].pubkey, margin);
+        assert!(ix.accounts[3].is_writable);
+        assert_eq!(ix.data[8], nonce);
+        assert_eq!(client.get_zod_margin(&authority), margin);
+    }
+
+    #[test]
+    fn test_auction_metas() {
+        let client = client();
+        let liqor = Pubkey::new_unique();
+        let liqee_margin = Pubkey::new_unique();
+        let (auction, nonce) =
+            program_address(&[liqee_margin.as_ref(), client.zod_state.as_ref(), b"zodauctionv1"]);
+
+        let ix = client.start_liquidation_auction(&liqor, &liqee_margin, &[]);
+        assert_eq!(ix.accounts[5].pubkey, auction);
+        assert_eq!(ix.data[8], nonce);
+
+        let ix = client.liquidate_zod_position(&liqor, &liqee_margin, &Pubkey::new_unique(), vec![], &[]);
+        let (liqor_margin, _) = program_address(&[liqor.as_ref(), client.zod_state.as_ref(), b"zodmarginv2"]);
+        assert_eq!(ix.accounts[4].pubkey, liqor_margin);
+        assert_eq!(ix.accounts[6].pubkey, auction);
+    }
+
+    #[test]
+    fn test_insurance_withdrawal_metas() {
+        let client = client();
+        let authority = Pubkey::new_unique();
+        let ix = client.request_insurance_withdrawal(&authority, 1);
+        let (withdrawal, nonce) =
+            program_address(&[authority.as_ref(), client.zod_state.as_ref(), b"zodinsurancewithdrawv1"]);
+        let (escrow, _) = program_address(&[withdrawal.as_ref(), b"zodinsuranceescrowv1"]);
+
+        assert_eq!(ix.accounts[4].pubkey, withdrawal);
+        assert_eq!(ix.accounts[5].pubkey, escrow);
+        assert_eq!(ix.data[8], nonce);
+
+        let ix = client.cancel_insurance_withdrawal(&authority, 1);
+        assert_eq!(ix.accounts[3].pubkey, withdrawal);
+        assert_eq!(ix.accounts[4].pubkey, escrow);
+    }
+
+    #[test]
+    fn test_oracle_history_metas() {
+        let client = client();
+        let ix = client.init_oracle_history(600);
+        let (oracle_history, nonce) = program_address(&[client.zod_state.as_ref(), b"zodoraclehistoryv1"]);
+
+        assert_eq!(ix.accounts[2].pubkey, oracle_history);
+        assert_eq!(ix.data[8], nonce);
+        assert_eq!(client.update_oracle_history(&[]).accounts[3].pubkey, oracle_history);
+    }
+
+    #[test]
+    fn test_remaining_accounts_follow_metas() {
+        let client = client();
+        let oracle = Pubkey::new_unique();
+        let ix = client.update_oracle_history(&[oracle]);
+
+        assert_eq!(ix.accounts.len(), 5);
+        assert_eq!(ix.accounts[4].pubkey, oracle);
+        assert!(!ix.accounts[4].is_writable && !ix.accounts[4].is_signer);
+    }
+}